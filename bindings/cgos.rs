@@ -0,0 +1,270 @@
+// Bindings for the parts of Cgos.h used by this crate, written by hand after the CGOS API.
+// build.rs uses them when the bindgen feature is disabled or the header cannot be found.
+pub type cgosret_bool = ::std::os::raw::c_uint;
+pub type HCGOS = ::std::os::raw::c_uint;
+
+pub const CGOS_BOARD_CLASS_CPU: u32 = 65536;
+pub const CGOS_BOARD_CLASS_VGA: u32 = 131072;
+pub const CGOS_BOARD_CLASS_IO: u32 = 262144;
+pub const CGOS_BOARD_MAX_SIZE_ID_STRING: u32 = 16;
+pub const CGOS_BOARD_MAX_SIZE_SERIAL_STRING: u32 = 16;
+pub const CGOS_BOARD_MAX_SIZE_PART_STRING: u32 = 16;
+pub const CGOS_BOARD_MAX_SIZE_EAN_STRING: u32 = 16;
+
+pub const CGOS_SENSOR_ACTIVE: u32 = 1;
+pub const CGOS_SENSOR_ALARM: u32 = 2;
+pub const CGOS_SENSOR_BROKEN: u32 = 4;
+pub const CGOS_SENSOR_SHORTCIRCUIT: u32 = 8;
+
+pub const CGOS_TEMP_CPU: u32 = 65536;
+pub const CGOS_TEMP_BOX: u32 = 131072;
+pub const CGOS_TEMP_ENV: u32 = 196608;
+pub const CGOS_TEMP_BOARD: u32 = 262144;
+pub const CGOS_TEMP_BACKPLANE: u32 = 327680;
+pub const CGOS_TEMP_CHIPSETS: u32 = 393216;
+pub const CGOS_TEMP_VIDEO: u32 = 458752;
+pub const CGOS_TEMP_OTHER: u32 = 524288;
+pub const CGOS_TEMP_TOPDIMM_ENV: u32 = 589824;
+pub const CGOS_TEMP_BOTDIMM_ENV: u32 = 655360;
+
+pub const CGOS_FAN_CPU: u32 = 65536;
+pub const CGOS_FAN_BOX: u32 = 131072;
+pub const CGOS_FAN_ENV: u32 = 196608;
+pub const CGOS_FAN_CHIPSET: u32 = 262144;
+pub const CGOS_FAN_VIDEO: u32 = 327680;
+pub const CGOS_FAN_OTHER: u32 = 393216;
+
+pub const CGOS_VOLTAGE_CPU_CORE: u32 = 65536;
+pub const CGOS_VOLTAGE_CPU_IO: u32 = 131072;
+pub const CGOS_VOLTAGE_DC: u32 = 196608;
+pub const CGOS_VOLTAGE_DC_STANDBY: u32 = 262144;
+pub const CGOS_VOLTAGE_BAT_CMOS: u32 = 327680;
+pub const CGOS_VOLTAGE_BAT_POWER: u32 = 393216;
+pub const CGOS_VOLTAGE_AC: u32 = 458752;
+pub const CGOS_VOLTAGE_OTHER: u32 = 524288;
+pub const CGOS_VOLTAGE_5V_S0: u32 = 589824;
+pub const CGOS_VOLTAGE_5V_S5: u32 = 655360;
+pub const CGOS_VOLTAGE_33V_S0: u32 = 720896;
+pub const CGOS_VOLTAGE_33V_S5: u32 = 786432;
+pub const CGOS_VOLTAGE_VCOREA: u32 = 851968;
+pub const CGOS_VOLTAGE_VCOREB: u32 = 917504;
+pub const CGOS_VOLTAGE_12V_S0: u32 = 983040;
+
+pub const CGOS_STORAGE_AREA_UNKNOWN: u32 = 0;
+pub const CGOS_STORAGE_AREA_EEPROM: u32 = 65536;
+pub const CGOS_STORAGE_AREA_FLASH: u32 = 131072;
+pub const CGOS_STORAGE_AREA_CMOS: u32 = 196608;
+pub const CGOS_STORAGE_AREA_RAM: u32 = 262144;
+
+pub const CGOS_WDOG_MODE_REBOOT_PC: u32 = 0;
+pub const CGOS_WDOG_MODE_RESTART_OS: u32 = 1;
+pub const CGOS_WDOG_MODE_STAGED: u32 = 128;
+pub const CGOS_WDOG_OPMODE_DISABLED: u32 = 0;
+pub const CGOS_WDOG_OPMODE_ONETIME_TRIG: u32 = 1;
+pub const CGOS_WDOG_OPMODE_SINGLE_EVENT: u32 = 2;
+pub const CGOS_WDOG_OPMODE_EVENT_REPEAT: u32 = 3;
+pub const CGOS_WDOG_EVENT_INT: u32 = 0;
+pub const CGOS_WDOG_EVENT_SCI: u32 = 1;
+pub const CGOS_WDOG_EVENT_RST: u32 = 2;
+pub const CGOS_WDOG_EVENT_BTN: u32 = 3;
+pub const CGOS_WDOG_EXPIRE_STAGES: u32 = 3;
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct CGOSTIME {
+    pub wYear: ::std::os::raw::c_ushort,
+    pub wMonth: ::std::os::raw::c_ushort,
+    pub wDayOfWeek: ::std::os::raw::c_ushort,
+    pub wDay: ::std::os::raw::c_ushort,
+    pub wHour: ::std::os::raw::c_ushort,
+    pub wMinute: ::std::os::raw::c_ushort,
+    pub wSecond: ::std::os::raw::c_ushort,
+    pub wMilliseconds: ::std::os::raw::c_ushort,
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct CGOSBOARDINFOA {
+    pub dwSize: ::std::os::raw::c_uint,
+    pub dwFlags: ::std::os::raw::c_uint,
+    pub szReserved: [::std::os::raw::c_char; 16usize],
+    pub szBoard: [::std::os::raw::c_char; 16usize],
+    pub szBoardSub: [::std::os::raw::c_char; 16usize],
+    pub szManufacturer: [::std::os::raw::c_char; 16usize],
+    pub stManufacturingDate: CGOSTIME,
+    pub stLastRepairDate: CGOSTIME,
+    pub szSerialNumber: [::std::os::raw::c_char; 16usize],
+    pub wProductRevision: ::std::os::raw::c_ushort,
+    pub wSystemBiosRevision: ::std::os::raw::c_ushort,
+    pub wBiosInterfaceRevision: ::std::os::raw::c_ushort,
+    pub wBiosInterfaceBuildRevision: ::std::os::raw::c_ushort,
+    pub dwClasses: ::std::os::raw::c_uint,
+    pub dwPrimaryClass: ::std::os::raw::c_uint,
+    pub dwRepairCounter: ::std::os::raw::c_uint,
+    pub szPartNumber: [::std::os::raw::c_char; 16usize],
+    pub szEAN: [::std::os::raw::c_char; 16usize],
+    pub dwManufacturer: ::std::os::raw::c_uint,
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct CGOSTEMPERATUREINFO {
+    pub dwSize: ::std::os::raw::c_uint,
+    pub dwType: ::std::os::raw::c_uint,
+    pub dwFlags: ::std::os::raw::c_uint,
+    pub dwAlarm: ::std::os::raw::c_uint,
+    pub dwRes: ::std::os::raw::c_uint,
+    pub dwMin: ::std::os::raw::c_uint,
+    pub dwMax: ::std::os::raw::c_uint,
+    pub dwAlarmHi: ::std::os::raw::c_uint,
+    pub dwHystHi: ::std::os::raw::c_uint,
+    pub dwAlarmLo: ::std::os::raw::c_uint,
+    pub dwHystLo: ::std::os::raw::c_uint,
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct CGOSFANINFO {
+    pub dwSize: ::std::os::raw::c_uint,
+    pub dwType: ::std::os::raw::c_uint,
+    pub dwFlags: ::std::os::raw::c_uint,
+    pub dwAlarm: ::std::os::raw::c_uint,
+    pub dwSpeedNom: ::std::os::raw::c_uint,
+    pub dwMin: ::std::os::raw::c_uint,
+    pub dwMax: ::std::os::raw::c_uint,
+    pub dwAlarmHi: ::std::os::raw::c_uint,
+    pub dwHystHi: ::std::os::raw::c_uint,
+    pub dwAlarmLo: ::std::os::raw::c_uint,
+    pub dwHystLo: ::std::os::raw::c_uint,
+    pub dwOutMin: ::std::os::raw::c_uint,
+    pub dwOutMax: ::std::os::raw::c_uint,
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct CGOSVOLTAGEINFO {
+    pub dwSize: ::std::os::raw::c_uint,
+    pub dwType: ::std::os::raw::c_uint,
+    pub dwNom: ::std::os::raw::c_uint,
+    pub dwFlags: ::std::os::raw::c_uint,
+    pub dwAlarm: ::std::os::raw::c_uint,
+    pub dwRes: ::std::os::raw::c_uint,
+    pub dwMin: ::std::os::raw::c_uint,
+    pub dwMax: ::std::os::raw::c_uint,
+    pub dwAlarmHi: ::std::os::raw::c_uint,
+    pub dwHystHi: ::std::os::raw::c_uint,
+    pub dwAlarmLo: ::std::os::raw::c_uint,
+    pub dwHystLo: ::std::os::raw::c_uint,
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct CGOSWDINFO {
+    pub dwSize: ::std::os::raw::c_uint,
+    pub dwFlags: ::std::os::raw::c_uint,
+    pub dwMinTimeout: ::std::os::raw::c_uint,
+    pub dwMaxTimeout: ::std::os::raw::c_uint,
+    pub dwMinDelay: ::std::os::raw::c_uint,
+    pub dwMaxDelay: ::std::os::raw::c_uint,
+    pub dwOpModes: ::std::os::raw::c_uint,
+    pub dwMaxStageCount: ::std::os::raw::c_uint,
+    pub dwEvents: ::std::os::raw::c_uint,
+    pub dwType: ::std::os::raw::c_uint,
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct CGOSWDSTAGE {
+    pub dwTimeout: ::std::os::raw::c_uint,
+    pub dwEvent: ::std::os::raw::c_uint,
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct CGOSWDCONFIG {
+    pub dwSize: ::std::os::raw::c_uint,
+    pub dwTimeout: ::std::os::raw::c_uint,
+    pub dwDelay: ::std::os::raw::c_uint,
+    pub dwMode: ::std::os::raw::c_uint,
+    pub dwOpMode: ::std::os::raw::c_uint,
+    pub dwStageCount: ::std::os::raw::c_uint,
+    pub stStages: [CGOSWDSTAGE; 3usize],
+}
+
+extern "C" {
+    pub fn CgosLibGetVersion() -> ::std::os::raw::c_uint;
+    pub fn CgosLibInitialize() -> cgosret_bool;
+    pub fn CgosLibUninitialize() -> cgosret_bool;
+    pub fn CgosLibIsAvailable() -> cgosret_bool;
+    pub fn CgosLibInstall(install: ::std::os::raw::c_uint) -> cgosret_bool;
+    pub fn CgosLibGetDrvVersion() -> ::std::os::raw::c_uint;
+    pub fn CgosLibGetLastError() -> ::std::os::raw::c_uint;
+    pub fn CgosLibSetLastErrorAddress(pErrNo: *mut ::std::os::raw::c_uint) -> cgosret_bool;
+
+    pub fn CgosBoardCount(dwClass: ::std::os::raw::c_uint, dwFlags: ::std::os::raw::c_uint) -> ::std::os::raw::c_uint;
+    pub fn CgosBoardOpen(dwClass: ::std::os::raw::c_uint, dwNum: ::std::os::raw::c_uint, dwFlags: ::std::os::raw::c_uint, phCgos: *mut HCGOS) -> cgosret_bool;
+    pub fn CgosBoardOpenByNameA(pszName: *const ::std::os::raw::c_char, phCgos: *mut HCGOS) -> cgosret_bool;
+    pub fn CgosBoardClose(hCgos: HCGOS) -> cgosret_bool;
+    pub fn CgosBoardGetNameA(hCgos: HCGOS, pszName: *mut ::std::os::raw::c_char, dwSize: ::std::os::raw::c_uint) -> cgosret_bool;
+    pub fn CgosBoardGetInfoA(hCgos: HCGOS, pBoardInfo: *mut CGOSBOARDINFOA) -> cgosret_bool;
+    pub fn CgosBoardGetBootCounter(hCgos: HCGOS, pdwCount: *mut ::std::os::raw::c_uint) -> cgosret_bool;
+    pub fn CgosBoardGetRunningTimeMeter(hCgos: HCGOS, pdwCount: *mut ::std::os::raw::c_uint) -> cgosret_bool;
+    pub fn CgosBoardGetOption(hCgos: HCGOS, dwOption: ::std::os::raw::c_uint, pdwSetting: *mut ::std::os::raw::c_uint) -> cgosret_bool;
+    pub fn CgosBoardSetOption(hCgos: HCGOS, dwOption: ::std::os::raw::c_uint, dwSetting: ::std::os::raw::c_uint) -> cgosret_bool;
+    pub fn CgosBoardGetBootErrorLog(hCgos: HCGOS, dwType: ::std::os::raw::c_uint, pdwLogType: *mut ::std::os::raw::c_uint, pBytes: *mut ::std::os::raw::c_uchar, pdwLen: *mut ::std::os::raw::c_uint) -> cgosret_bool;
+
+    pub fn CgosStorageAreaCount(hCgos: HCGOS, dwUnit: ::std::os::raw::c_uint) -> ::std::os::raw::c_uint;
+    pub fn CgosStorageAreaType(hCgos: HCGOS, dwUnit: ::std::os::raw::c_uint) -> ::std::os::raw::c_uint;
+    pub fn CgosStorageAreaSize(hCgos: HCGOS, dwUnit: ::std::os::raw::c_uint) -> ::std::os::raw::c_uint;
+    pub fn CgosStorageAreaBlockSize(hCgos: HCGOS, dwUnit: ::std::os::raw::c_uint) -> ::std::os::raw::c_uint;
+    pub fn CgosStorageAreaRead(hCgos: HCGOS, dwUnit: ::std::os::raw::c_uint, dwOffset: ::std::os::raw::c_uint, pBytes: *mut ::std::os::raw::c_uchar, dwLen: ::std::os::raw::c_uint) -> cgosret_bool;
+    pub fn CgosStorageAreaWrite(hCgos: HCGOS, dwUnit: ::std::os::raw::c_uint, dwOffset: ::std::os::raw::c_uint, pBytes: *mut ::std::os::raw::c_uchar, dwLen: ::std::os::raw::c_uint) -> cgosret_bool;
+    pub fn CgosStorageAreaErase(hCgos: HCGOS, dwUnit: ::std::os::raw::c_uint, dwOffset: ::std::os::raw::c_uint, dwLen: ::std::os::raw::c_uint) -> cgosret_bool;
+    pub fn CgosStorageAreaEraseStatus(hCgos: HCGOS, dwUnit: ::std::os::raw::c_uint, dwOffset: ::std::os::raw::c_uint, dwLen: ::std::os::raw::c_uint, lpStatus: *mut ::std::os::raw::c_uint) -> cgosret_bool;
+    pub fn CgosStorageAreaLock(hCgos: HCGOS, dwUnit: ::std::os::raw::c_uint, dwFlags: ::std::os::raw::c_uint, pBytes: *mut ::std::os::raw::c_uchar, dwLen: ::std::os::raw::c_uint) -> cgosret_bool;
+    pub fn CgosStorageAreaUnlock(hCgos: HCGOS, dwUnit: ::std::os::raw::c_uint, dwFlags: ::std::os::raw::c_uint, pBytes: *mut ::std::os::raw::c_uchar, dwLen: ::std::os::raw::c_uint) -> cgosret_bool;
+    pub fn CgosStorageAreaIsLocked(hCgos: HCGOS, dwUnit: ::std::os::raw::c_uint, dwFlags: ::std::os::raw::c_uint) -> cgosret_bool;
+
+    pub fn CgosIOCount(hCgos: HCGOS) -> ::std::os::raw::c_uint;
+    pub fn CgosIOIsAvailable(hCgos: HCGOS, dwUnit: ::std::os::raw::c_uint) -> cgosret_bool;
+    pub fn CgosIORead(hCgos: HCGOS, dwUnit: ::std::os::raw::c_uint, pdwData: *mut ::std::os::raw::c_uint) -> cgosret_bool;
+    pub fn CgosIOWrite(hCgos: HCGOS, dwUnit: ::std::os::raw::c_uint, dwData: ::std::os::raw::c_uint) -> cgosret_bool;
+    pub fn CgosIOXorAndXor(hCgos: HCGOS, dwUnit: ::std::os::raw::c_uint, dwXorMask1: ::std::os::raw::c_uint, dwAndMask: ::std::os::raw::c_uint, dwXorMask2: ::std::os::raw::c_uint) -> cgosret_bool;
+    pub fn CgosIOGetDirectionCaps(hCgos: HCGOS, dwUnit: ::std::os::raw::c_uint, pdwInputs: *mut ::std::os::raw::c_uint, pdwOutputs: *mut ::std::os::raw::c_uint) -> cgosret_bool;
+    pub fn CgosIOGetDirection(hCgos: HCGOS, dwUnit: ::std::os::raw::c_uint, pdwData: *mut ::std::os::raw::c_uint) -> cgosret_bool;
+    pub fn CgosIOSetDirection(hCgos: HCGOS, dwUnit: ::std::os::raw::c_uint, dwData: ::std::os::raw::c_uint) -> cgosret_bool;
+    pub fn CgosIOGetNameA(hCgos: HCGOS, dwUnit: ::std::os::raw::c_uint, pszName: *mut ::std::os::raw::c_char, dwSize: ::std::os::raw::c_uint) -> cgosret_bool;
+
+    pub fn CgosWDogCount(hCgos: HCGOS) -> ::std::os::raw::c_uint;
+    pub fn CgosWDogIsAvailable(hCgos: HCGOS, dwUnit: ::std::os::raw::c_uint) -> cgosret_bool;
+    pub fn CgosWDogTrigger(hCgos: HCGOS, dwUnit: ::std::os::raw::c_uint) -> cgosret_bool;
+    pub fn CgosWDogGetConfigStruct(hCgos: HCGOS, dwUnit: ::std::os::raw::c_uint, pConfig: *mut CGOSWDCONFIG) -> cgosret_bool;
+    pub fn CgosWDogSetConfigStruct(hCgos: HCGOS, dwUnit: ::std::os::raw::c_uint, pConfig: *mut CGOSWDCONFIG) -> cgosret_bool;
+    pub fn CgosWDogSetConfig(hCgos: HCGOS, dwUnit: ::std::os::raw::c_uint, timeout: ::std::os::raw::c_uint, delay: ::std::os::raw::c_uint, mode: ::std::os::raw::c_uint) -> cgosret_bool;
+    pub fn CgosWDogDisable(hCgos: HCGOS, dwUnit: ::std::os::raw::c_uint) -> cgosret_bool;
+    pub fn CgosWDogGetInfo(hCgos: HCGOS, dwUnit: ::std::os::raw::c_uint, pInfo: *mut CGOSWDINFO) -> cgosret_bool;
+
+    pub fn CgosTemperatureCount(hCgos: HCGOS) -> ::std::os::raw::c_uint;
+    pub fn CgosTemperatureGetInfo(hCgos: HCGOS, dwUnit: ::std::os::raw::c_uint, pInfo: *mut CGOSTEMPERATUREINFO) -> cgosret_bool;
+    pub fn CgosTemperatureGetCurrent(hCgos: HCGOS, dwUnit: ::std::os::raw::c_uint, pdwSetting: *mut ::std::os::raw::c_uint, pdwStatus: *mut ::std::os::raw::c_uint) -> cgosret_bool;
+    pub fn CgosTemperatureSetLimits(hCgos: HCGOS, dwUnit: ::std::os::raw::c_uint, pInfo: *mut CGOSTEMPERATUREINFO) -> cgosret_bool;
+    pub fn CgosFanCount(hCgos: HCGOS) -> ::std::os::raw::c_uint;
+    pub fn CgosFanGetInfo(hCgos: HCGOS, dwUnit: ::std::os::raw::c_uint, pInfo: *mut CGOSFANINFO) -> cgosret_bool;
+    pub fn CgosFanGetCurrent(hCgos: HCGOS, dwUnit: ::std::os::raw::c_uint, pdwSetting: *mut ::std::os::raw::c_uint, pdwStatus: *mut ::std::os::raw::c_uint) -> cgosret_bool;
+    pub fn CgosFanSetLimits(hCgos: HCGOS, dwUnit: ::std::os::raw::c_uint, pInfo: *mut CGOSFANINFO) -> cgosret_bool;
+    pub fn CgosVoltageCount(hCgos: HCGOS) -> ::std::os::raw::c_uint;
+    pub fn CgosVoltageGetInfo(hCgos: HCGOS, dwUnit: ::std::os::raw::c_uint, pInfo: *mut CGOSVOLTAGEINFO) -> cgosret_bool;
+    pub fn CgosVoltageGetCurrent(hCgos: HCGOS, dwUnit: ::std::os::raw::c_uint, pdwSetting: *mut ::std::os::raw::c_uint, pdwStatus: *mut ::std::os::raw::c_uint) -> cgosret_bool;
+    pub fn CgosVoltageSetLimits(hCgos: HCGOS, dwUnit: ::std::os::raw::c_uint, pInfo: *mut CGOSVOLTAGEINFO) -> cgosret_bool;
+
+    pub fn CgosPerformanceGetCurrent(hCgos: HCGOS, dwUnit: ::std::os::raw::c_uint, pdwSetting: *mut ::std::os::raw::c_uint) -> cgosret_bool;
+    pub fn CgosPerformanceSetCurrent(hCgos: HCGOS, dwUnit: ::std::os::raw::c_uint, dwSetting: ::std::os::raw::c_uint) -> cgosret_bool;
+    pub fn CgosPerformanceGetPolicyCaps(hCgos: HCGOS, dwUnit: ::std::os::raw::c_uint, pdwSetting: *mut ::std::os::raw::c_uint) -> cgosret_bool;
+    pub fn CgosPerformanceGetPolicy(hCgos: HCGOS, dwUnit: ::std::os::raw::c_uint, pdwSetting: *mut ::std::os::raw::c_uint) -> cgosret_bool;
+    pub fn CgosPerformanceSetPolicy(hCgos: HCGOS, dwUnit: ::std::os::raw::c_uint, dwSetting: ::std::os::raw::c_uint) -> cgosret_bool;
+
+    pub fn CgosCgbcGetInfo(hCgos: HCGOS, dwType: ::std::os::raw::c_uint, pInfo: *mut ::std::os::raw::c_void) -> cgosret_bool;
+    pub fn CgosCgbcSetControl(hCgos: HCGOS, dwLine: ::std::os::raw::c_uint, dwSetting: ::std::os::raw::c_uint) -> cgosret_bool;
+    pub fn CgosCgbcReadWrite(hCgos: HCGOS, bDataByte: ::std::os::raw::c_uchar, pDataByte: *mut ::std::os::raw::c_uchar, dwClockDelay: ::std::os::raw::c_uint, dwTimeoutDelay: ::std::os::raw::c_uint) -> cgosret_bool;
+    pub fn CgosCgbcHandleCommand(hCgos: HCGOS, pBytesWrite: *mut ::std::os::raw::c_uchar, dwLenWrite: ::std::os::raw::c_uint, pBytesRead: *mut ::std::os::raw::c_uchar, dwLenRead: ::std::os::raw::c_uint, pdwStatus: *mut ::std::os::raw::c_uint) -> cgosret_bool;
+}
@@ -1,18 +1,58 @@
-use std::{env::var, path::PathBuf};
+#[cfg(feature = "bindgen")]
+use std::panic::catch_unwind;
+use std::{env::var, fs::copy, path::PathBuf};
 
-use bindgen::{Builder, CargoCallbacks};
+#[cfg(feature = "bindgen")]
+use bindgen::{Bindings, Builder, CargoCallbacks};
 
 fn main() {
-    let out_path = PathBuf::from(var("OUT_DIR").unwrap());
+    // The mock backend replaces libcgos entirely, neither Cgos.h nor the library are needed.
+    if var("CARGO_FEATURE_MOCK").is_ok() {
+        return;
+    }
+    let out_path = PathBuf::from(var("OUT_DIR").unwrap()).join("bindings.rs");
     println!("cargo:rerun-if-changed=wrapper.h");
-    println!("cargo:rustc-link-lib=cgos");
-    let bindings = Builder::default()
-        .header("wrapper.h")
-        .parse_callbacks(Box::new(CargoCallbacks))
-        .clang_args(vec!["-I."])
-        .generate()
-        .expect("failed to generate bindings");
-    bindings
-        .write_to_file(out_path.join("bindings.rs"))
-        .expect("failed to write bindings");
+    println!("cargo:rerun-if-changed=bindings/cgos.rs");
+    // Windows has no standard location for the CGOS SDK, these point to its header and import
+    // library (Cgos.lib).
+    println!("cargo:rerun-if-env-changed=CGOS_INCLUDE_DIR");
+    println!("cargo:rerun-if-env-changed=CGOS_LIB_DIR");
+    // With the dynamic backend libcgos is loaded at runtime, only the header is needed.
+    if var("CARGO_FEATURE_DYNAMIC").is_err() {
+        if let Ok(lib_dir) = var("CGOS_LIB_DIR") {
+            println!("cargo:rustc-link-search=native={lib_dir}");
+        }
+        println!("cargo:rustc-link-lib=cgos");
+    }
+    #[cfg(feature = "bindgen")]
+    match generate_bindings() {
+        Some(bindings) => {
+            bindings
+                .write_to_file(&out_path)
+                .expect("failed to write bindings");
+            return;
+        }
+        None => println!("cargo:warning=failed to generate bindings, using bindings/cgos.rs"),
+    }
+    copy("bindings/cgos.rs", out_path).expect("failed to copy bindings");
+}
+
+/// Generates bindings from the installed Cgos.h, `None` if it or libclang cannot be found.
+#[cfg(feature = "bindgen")]
+fn generate_bindings() -> Option<Bindings> {
+    let mut clang_args = vec!["-I.".to_string()];
+    if let Ok(include_dir) = var("CGOS_INCLUDE_DIR") {
+        clang_args.push(format!("-I{include_dir}"));
+    }
+    // bindgen panics instead of returning an error when libclang is missing.
+    catch_unwind(|| {
+        Builder::default()
+            .header("wrapper.h")
+            .parse_callbacks(Box::new(CargoCallbacks))
+            .clang_args(clang_args)
+            .generate()
+            .ok()
+    })
+    .ok()
+    .flatten()
 }
@@ -0,0 +1,204 @@
+//! Command line access to boards through CGOS, enabled with the `cli` feature.
+
+mod storage;
+mod watchdog;
+
+use std::fmt::Display;
+
+use clap::{Parser, Subcommand};
+use serde::Serialize;
+use serde_json::json;
+
+use cgos::{
+    board::{Board, BoardClass},
+    congatec::Congatec,
+    error::Result,
+    sensor::Reading,
+    status::Status,
+};
+
+use storage::StorageCommand;
+use watchdog::WatchdogCommand;
+
+#[derive(Parser)]
+#[command(about = "Inspect congatec boards through CGOS", version)]
+struct Arguments {
+    /// Board index among all attached boards, all boards if omitted.
+    #[arg(long, global = true)]
+    board: Option<usize>,
+    /// Print JSON instead of text.
+    #[arg(long, global = true)]
+    json: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Library and driver versions.
+    Versions,
+    /// Board identification and counters.
+    Info,
+    /// Current readings and info of all sensors.
+    Sensors,
+    /// Storage areas of the board, the first board if --board is omitted.
+    Storage {
+        #[command(subcommand)]
+        command: StorageCommand,
+    },
+    /// Watchdog of the board, the first board if --board is omitted.
+    Wdog {
+        /// Watchdog index on the board.
+        #[arg(long, default_value = "0")]
+        index: usize,
+        #[command(subcommand)]
+        command: WatchdogCommand,
+    },
+}
+
+fn main() -> Result<()> {
+    let arguments = Arguments::parse();
+    let library = Congatec::new()?;
+    match arguments.command {
+        Command::Versions => versions(&library, arguments.json),
+        Command::Info => {
+            for board in boards(&library, arguments.board)? {
+                info(&board, arguments.json)?;
+            }
+            Ok(())
+        }
+        Command::Sensors => {
+            for board in boards(&library, arguments.board)? {
+                sensors(&board, arguments.json)?;
+            }
+            Ok(())
+        }
+        Command::Storage { command } => {
+            let board = library.get_board(BoardClass::ALL, arguments.board.unwrap_or(0))?;
+            storage::run(&board, command, arguments.json)
+        }
+        Command::Wdog { index, command } => {
+            let board = library.get_board(BoardClass::ALL, arguments.board.unwrap_or(0))?;
+            watchdog::run(&board, index, command, arguments.json)
+        }
+    }
+}
+
+fn boards(library: &Congatec, index: Option<usize>) -> Result<Vec<Board<'_>>> {
+    match index {
+        Some(index) => Ok(vec![library.get_board(BoardClass::ALL, index)?]),
+        None => library.boards(BoardClass::ALL).collect(),
+    }
+}
+
+fn versions(library: &Congatec, json: bool) -> Result<()> {
+    let (library_version, driver_version) = (library.version(), library.driver_version());
+    match json {
+        true => println!(
+            "{}",
+            json!({ "library": library_version, "driver": driver_version })
+        ),
+        false => {
+            println!("library {library_version}");
+            println!("driver  {driver_version}");
+        }
+    }
+    Ok(())
+}
+
+fn info(board: &Board, json: bool) -> Result<()> {
+    let info = board.info()?;
+    let boot_count = board.boot_count()?;
+    let running_time = board.running_time()?;
+    if json {
+        println!(
+            "{}",
+            json!({
+                "name": board.name()?,
+                "info": info,
+                "boot_count": boot_count,
+                "running_time_hours": running_time.as_secs() / 3600,
+            })
+        );
+        return Ok(());
+    }
+    println!("{}", board.name()?);
+    println!("  {info}");
+    println!("  classes          {}", info.classes);
+    println!("  part number      {}", info.part_number);
+    println!("  EAN              {}", info.european_article_number);
+    println!("  manufactured     {}", info.manufacturing_date);
+    println!("  last repair      {}", info.last_repair_date);
+    println!("  repairs          {}", info.repair_counter);
+    println!("  boots            {boot_count}");
+    println!("  running time     {} h", running_time.as_secs() / 3600);
+    Ok(())
+}
+
+fn sensors<'library>(board: &'library Board<'library>, json: bool) -> Result<()> {
+    if !json {
+        println!("{}", board.name()?);
+    }
+    for (index, sensor) in board.temperatures().enumerate() {
+        let sensor = sensor?;
+        let (value, status) = sensor.current()?;
+        print_sensor(
+            json,
+            "temperature",
+            index,
+            value,
+            Reading::Temperature(value),
+            status,
+            sensor.info()?,
+        );
+    }
+    for (index, sensor) in board.fans().enumerate() {
+        let sensor = sensor?;
+        let (value, status) = sensor.current()?;
+        print_sensor(
+            json,
+            "fan",
+            index,
+            value,
+            Reading::Fan(value),
+            status,
+            sensor.info()?,
+        );
+    }
+    for (index, sensor) in board.voltages().enumerate() {
+        let sensor = sensor?;
+        let (value, status) = sensor.current()?;
+        print_sensor(
+            json,
+            "voltage",
+            index,
+            value,
+            Reading::Voltage(value),
+            status,
+            sensor.info()?,
+        );
+    }
+    Ok(())
+}
+
+/// Prints one line per sensor, JSON objects are printed as JSON lines with the raw `value`.
+fn print_sensor(
+    json: bool,
+    kind: &str,
+    index: usize,
+    value: impl Serialize,
+    reading: Reading,
+    status: Status,
+    info: impl Display + Serialize,
+) {
+    match json {
+        true => println!(
+            "{}",
+            json!({ "kind": kind, "index": index, "value": value, "status": status, "info": info })
+        ),
+        false => {
+            let name = format!("{kind} {index}");
+            println!("  {name:<16} {reading} ({status}), {info}");
+        }
+    }
+}
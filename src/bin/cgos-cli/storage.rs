@@ -0,0 +1,201 @@
+use std::{
+    fs::File,
+    io::{self, stdin, stdout, BufRead, Read, Write},
+    path::PathBuf,
+    process::exit,
+    time::Duration,
+};
+
+use clap::{Args, Subcommand};
+use serde_json::json;
+
+use cgos::{board::Board, error::Result, storage_area::StorageArea};
+
+const ERASE_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Subcommand)]
+pub enum StorageCommand {
+    /// All storage areas of the board.
+    List,
+    /// Hex and ASCII dump of an area.
+    Hexdump(Range),
+    /// Raw contents of an area.
+    Read {
+        #[command(flatten)]
+        range: Range,
+        /// Written to stdout if omitted.
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+    /// Writes a file into an area and reads it back to verify.
+    Write {
+        #[arg(long)]
+        area: usize,
+        #[arg(long, default_value = "0")]
+        offset: usize,
+        /// File to write, - for stdin which needs --yes.
+        #[arg(long)]
+        input: PathBuf,
+        /// Skips the confirmation.
+        #[arg(long)]
+        yes: bool,
+    },
+    /// Erases a range of an area, the whole area if no range is given.
+    Erase {
+        #[command(flatten)]
+        range: Range,
+        /// Skips the confirmation.
+        #[arg(long)]
+        yes: bool,
+    },
+}
+
+#[derive(Args)]
+pub struct Range {
+    #[arg(long)]
+    area: usize,
+    #[arg(long, default_value = "0")]
+    offset: usize,
+    /// Up to the end of the area if omitted.
+    #[arg(long)]
+    length: Option<usize>,
+}
+
+impl Range {
+    fn open<'library>(
+        &self,
+        board: &'library Board<'library>,
+    ) -> Result<(StorageArea<'library>, usize)> {
+        let area = board.get_storage_area_from_index(self.area)?;
+        let length = self
+            .length
+            .unwrap_or_else(|| area.size().saturating_sub(self.offset));
+        Ok((area, length))
+    }
+}
+
+pub fn run<'library>(
+    board: &'library Board<'library>,
+    command: StorageCommand,
+    json: bool,
+) -> Result<()> {
+    match command {
+        StorageCommand::List => list(board, json)?,
+        StorageCommand::Hexdump(range) => {
+            let (area, length) = range.open(board)?;
+            let mut data = vec![0; length];
+            area.read(range.offset, &mut data)?;
+            hexdump(range.offset, &data);
+        }
+        StorageCommand::Read { range, output } => {
+            let (area, length) = range.open(board)?;
+            let mut data = vec![0; length];
+            area.read(range.offset, &mut data)?;
+            match output {
+                Some(path) => File::create(path)?.write_all(&data)?,
+                None => stdout().write_all(&data)?,
+            }
+        }
+        StorageCommand::Write {
+            area,
+            offset,
+            input,
+            yes,
+        } => {
+            let from_stdin = input.as_os_str() == "-";
+            if from_stdin && !yes {
+                eprintln!("writing from stdin needs --yes, stdin cannot answer the confirmation");
+                exit(2);
+            }
+            let mut data = Vec::new();
+            match from_stdin {
+                true => stdin().read_to_end(&mut data)?,
+                false => File::open(input)?.read_to_end(&mut data)?,
+            };
+            let storage_area = board.get_storage_area_from_index(area)?;
+            confirm(
+                yes,
+                &format!(
+                    "write {} bytes to area {area} at offset {offset}?",
+                    data.len()
+                ),
+            )?;
+            storage_area.write_verified(offset, &data)?;
+        }
+        StorageCommand::Erase { range, yes } => {
+            let (area, length) = range.open(board)?;
+            confirm(
+                yes,
+                &format!(
+                    "erase {length} bytes of area {} at offset {}?",
+                    range.area, range.offset
+                ),
+            )?;
+            area.erase_and_wait(range.offset, length, ERASE_TIMEOUT)?;
+        }
+    }
+    Ok(())
+}
+
+fn list<'library>(board: &'library Board<'library>, json: bool) -> Result<()> {
+    for (index, area) in board.storage_areas().enumerate() {
+        let area = area?;
+        let (kind, size, block_size, locked) = (
+            area.kind(),
+            area.size(),
+            area.block_size(),
+            area.is_locked(),
+        );
+        match json {
+            true => println!(
+                "{}",
+                json!({
+                    "index": index,
+                    "kind": kind,
+                    "size": size,
+                    "block_size": block_size,
+                    "locked": locked,
+                })
+            ),
+            false => println!(
+                "area {index:<3} {kind:?}, {size} bytes, blocks of {block_size} bytes{}",
+                if locked { ", locked" } else { "" }
+            ),
+        }
+    }
+    Ok(())
+}
+
+/// Prints 16 bytes per line with their offset and ASCII, like `hexdump -C`.
+fn hexdump(offset: usize, data: &[u8]) {
+    for (line, chunk) in data.chunks(16).enumerate() {
+        let hex: Vec<_> = chunk.iter().map(|byte| format!("{byte:02x}")).collect();
+        let ascii: String = chunk
+            .iter()
+            .map(|&byte| match byte.is_ascii_graphic() || byte == b' ' {
+                true => byte as char,
+                false => '.',
+            })
+            .collect();
+        println!(
+            "{:08x}  {:<47}  |{ascii}|",
+            offset + line * 16,
+            hex.join(" ")
+        );
+    }
+}
+
+/// Asks on the terminal unless `yes` is given, exits if the answer is not yes.
+fn confirm(yes: bool, question: &str) -> io::Result<()> {
+    if yes {
+        return Ok(());
+    }
+    eprint!("{question} [y/N] ");
+    let mut answer = String::new();
+    stdin().lock().read_line(&mut answer)?;
+    if !matches!(answer.trim(), "y" | "Y" | "yes") {
+        eprintln!("aborted");
+        exit(1);
+    }
+    Ok(())
+}
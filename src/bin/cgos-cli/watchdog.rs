@@ -0,0 +1,68 @@
+use std::{thread::sleep, time::Duration};
+
+use clap::Subcommand;
+use serde_json::json;
+
+use cgos::{board::Board, error::Result};
+
+#[derive(Subcommand)]
+pub enum WatchdogCommand {
+    /// Current configuration.
+    Status,
+    /// Arms the watchdog to reboot the board unless triggered in time.
+    Arm {
+        /// Seconds until the board is rebooted.
+        #[arg(long, value_parser = seconds)]
+        timeout: Duration,
+        /// Keeps triggering every this many seconds until the process is killed, after which the
+        /// watchdog fires.
+        #[arg(long, value_parser = seconds)]
+        keep_alive: Option<Duration>,
+    },
+    /// Restarts the timeout of an armed watchdog.
+    Trigger,
+    Disable,
+}
+
+pub fn run<'library>(
+    board: &'library Board<'library>,
+    index: usize,
+    command: WatchdogCommand,
+    json: bool,
+) -> Result<()> {
+    let watchdog = board.get_watchdog(index)?;
+    match command {
+        WatchdogCommand::Status => {
+            let config = watchdog.config()?;
+            match json {
+                true => println!("{}", json!(config)),
+                false => println!("watchdog {index}: {config}"),
+            }
+        }
+        WatchdogCommand::Arm {
+            timeout,
+            keep_alive,
+        } => {
+            watchdog.set_timeout(timeout)?;
+            if let Some(interval) = keep_alive {
+                eprintln!("triggering every {interval:?}, the watchdog fires once killed");
+                loop {
+                    sleep(interval);
+                    watchdog.trigger()?;
+                }
+            }
+        }
+        WatchdogCommand::Trigger => watchdog.trigger()?,
+        WatchdogCommand::Disable => watchdog.disable()?,
+    }
+    Ok(())
+}
+
+/// Parses a positive number of seconds, zero would keep the watchdog busy or fire it at once.
+fn seconds(value: &str) -> std::result::Result<Duration, String> {
+    let seconds = value.parse::<f64>().map_err(|error| error.to_string())?;
+    match Duration::try_from_secs_f64(seconds) {
+        Ok(duration) if !duration.is_zero() => Ok(duration),
+        _ => Err(format!("{value} is not a positive number of seconds")),
+    }
+}
@@ -0,0 +1,26 @@
+//! Daemon serving all boards over D-Bus, enabled with the `dbus` feature.
+//!
+//! Claims `org.cgos` on the system bus, or on the session bus with `--session`.
+
+use std::{env::args, process::exit, sync::Arc, thread::park};
+
+use cgos::{
+    congatec::Congatec,
+    dbus::{serve, Bus},
+    error::Result,
+};
+
+fn main() -> Result<()> {
+    let bus = match args().nth(1).as_deref() {
+        None => Bus::System,
+        Some("--session") => Bus::Session,
+        Some(_) => {
+            eprintln!("usage: cgos-dbus [--session]");
+            exit(2);
+        }
+    };
+    let _connection = serve(Arc::new(Congatec::new()?), bus)?;
+    loop {
+        park();
+    }
+}
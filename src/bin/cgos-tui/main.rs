@@ -0,0 +1,208 @@
+//! Live terminal dashboard of a board's sensors and watchdogs, enabled with the `tui` feature.
+
+use std::{
+    io::{stdout, Stdout},
+    time::{Duration, Instant},
+};
+
+use clap::Parser;
+use crossterm::{
+    event::{poll, read, Event, KeyCode},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    widgets::{Block, Borders, Paragraph, Sparkline},
+    Frame, Terminal,
+};
+
+use cgos::{
+    board::{Board, BoardClass},
+    congatec::Congatec,
+    error::Result,
+    sensor::{Reading, Sensor},
+    status::Status,
+    watchdog::Watchdog,
+};
+
+/// Readings kept per sensor, more than any terminal is wide.
+const HISTORY_LENGTH: usize = 512;
+
+#[derive(Parser)]
+#[command(about = "Live dashboard of congatec board sensors", version)]
+struct Arguments {
+    /// Board index among all attached boards.
+    #[arg(long, default_value = "0")]
+    board: usize,
+    /// Milliseconds between readings.
+    #[arg(long, default_value = "1000")]
+    interval: u64,
+}
+
+struct Row<'library> {
+    name: String,
+    sensor: Sensor<'library>,
+    current: Option<(Reading, Status)>,
+    history: Vec<u64>,
+}
+
+struct Dashboard<'library> {
+    name: String,
+    rows: Vec<Row<'library>>,
+    watchdogs: Vec<Watchdog<'library>>,
+}
+
+impl<'library> Dashboard<'library> {
+    fn new(board: &'library Board<'library>) -> Result<Self> {
+        let mut rows = Vec::new();
+        for temperature in board.temperatures() {
+            let temperature = temperature?;
+            let name = format!("{} temperature", temperature.info()?.type_);
+            rows.push((name, Sensor::Temperature(temperature)));
+        }
+        for fan in board.fans() {
+            let fan = fan?;
+            let name = format!("{} fan", fan.info()?.type_);
+            rows.push((name, Sensor::Fan(fan)));
+        }
+        for voltage in board.voltages() {
+            let voltage = voltage?;
+            let name = format!("{} voltage", voltage.info()?.type_);
+            rows.push((name, Sensor::Voltage(voltage)));
+        }
+        Ok(Self {
+            name: board.name()?,
+            rows: rows
+                .into_iter()
+                .map(|(name, sensor)| Row {
+                    name,
+                    sensor,
+                    current: None,
+                    history: Vec::new(),
+                })
+                .collect(),
+            watchdogs: board.watchdogs().collect::<Result<_>>()?,
+        })
+    }
+
+    /// Failed readings are shown as missing and leave a gap in the history.
+    fn update(&mut self) {
+        for row in &mut self.rows {
+            row.current = row.sensor.current().ok();
+            let value = match row.current {
+                Some((Reading::Temperature(celsius), _)) => celsius.0.max(0.0) as u64,
+                Some((Reading::Fan(rpm), _)) => rpm.max(0) as u64,
+                Some((Reading::Voltage(millivolts), _)) => millivolts.0.into(),
+                None => 0,
+            };
+            if row.history.len() == HISTORY_LENGTH {
+                row.history.remove(0);
+            }
+            row.history.push(value);
+        }
+    }
+
+    fn draw(&self, frame: &mut Frame<CrosstermBackend<Stdout>>) {
+        let [sensors, watchdogs, help]: [Rect; 3] = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(3),
+                Constraint::Length(self.watchdogs.len() as u16 + 2),
+                Constraint::Length(1),
+            ])
+            .split(frame.size())
+            .as_ref()
+            .try_into()
+            .unwrap();
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" {} ", self.name));
+        let inner = block.inner(sensors);
+        frame.render_widget(block, sensors);
+        let lines = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(vec![Constraint::Length(1); self.rows.len() + 1])
+            .split(inner);
+        for (row, line) in self.rows.iter().zip(lines.iter()) {
+            let [label, sparkline]: [Rect; 2] = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Length(50), Constraint::Min(1)])
+                .split(*line)
+                .as_ref()
+                .try_into()
+                .unwrap();
+            let (text, style) = match row.current {
+                Some((reading, status)) if status.contains(Status::ALARM) => (
+                    format!("{:<24} {reading} ({status})", row.name),
+                    Style::default().fg(Color::Red),
+                ),
+                Some((reading, _)) => (format!("{:<24} {reading}", row.name), Style::default()),
+                None => (
+                    format!("{:<24} failed", row.name),
+                    Style::default().fg(Color::Yellow),
+                ),
+            };
+            frame.render_widget(Paragraph::new(text).style(style), label);
+            let shown = row.history.len().min(sparkline.width.into());
+            frame.render_widget(
+                Sparkline::default()
+                    .data(&row.history[row.history.len() - shown..])
+                    .style(style),
+                sparkline,
+            );
+        }
+
+        let states: Vec<_> = self
+            .watchdogs
+            .iter()
+            .enumerate()
+            .map(|(index, watchdog)| match watchdog.config() {
+                Ok(config) => format!("watchdog {index}: {config}"),
+                Err(error) => format!("watchdog {index}: {error}"),
+            })
+            .collect();
+        frame.render_widget(
+            Paragraph::new(states.join("\n"))
+                .block(Block::default().borders(Borders::ALL).title(" watchdogs ")),
+            watchdogs,
+        );
+        frame.render_widget(Paragraph::new("q to quit"), help);
+    }
+}
+
+fn main() -> Result<()> {
+    let arguments = Arguments::parse();
+    let library = Congatec::new()?;
+    let board = library.get_board(BoardClass::ALL, arguments.board)?;
+    let mut dashboard = Dashboard::new(&board)?;
+
+    enable_raw_mode()?;
+    execute!(stdout(), EnterAlternateScreen)?;
+    let result = run(&mut dashboard, Duration::from_millis(arguments.interval));
+    disable_raw_mode()?;
+    execute!(stdout(), LeaveAlternateScreen)?;
+    result
+}
+
+fn run(dashboard: &mut Dashboard, interval: Duration) -> Result<()> {
+    let mut terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
+    let mut next_update = Instant::now();
+    loop {
+        if Instant::now() >= next_update {
+            dashboard.update();
+            next_update += interval;
+        }
+        terminal.draw(|frame| dashboard.draw(frame))?;
+        if poll(next_update.saturating_duration_since(Instant::now()))? {
+            if let Event::Key(key) = read()? {
+                if matches!(key.code, KeyCode::Char('q') | KeyCode::Esc) {
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
@@ -1,3 +1,8 @@
+//! Raw CGOS declarations, public with the `unsafe-bindings` feature.
+//!
+//! Functions not wrapped by the crate can be called with the handles of its devices, as long as
+//! the [`Congatec`](crate::congatec::Congatec) they were opened from is alive.
+
 #![allow(clippy::all)]
 #![allow(dead_code)]
 #![allow(improper_ctypes)]
@@ -5,4 +10,210 @@
 #![allow(non_camel_case_types)]
 #![allow(non_snake_case)]
 
-include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+#[cfg(not(feature = "mock"))]
+pub(crate) mod libcgos {
+    include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+}
+
+#[cfg(all(feature = "dynamic", not(feature = "mock")))]
+pub(crate) use crate::dynamic::ffi as backend;
+#[cfg(feature = "mock")]
+pub(crate) use crate::mock::ffi as backend;
+#[cfg(not(any(feature = "dynamic", feature = "mock")))]
+pub(crate) use libcgos as backend;
+
+#[cfg(feature = "capture")]
+pub use crate::capture::ffi::*;
+#[cfg(not(feature = "capture"))]
+pub use backend::*;
+
+/// Invokes `$callback` with the signatures of all CGOS functions used by the crate, for backends
+/// wrapping them. Output pointers the function writes to are listed after `=>`, with their length
+/// in bytes unless it is the size of the pointee.
+#[cfg(any(feature = "capture", all(feature = "dynamic", not(feature = "mock"))))]
+macro_rules! cgos_functions {
+    ($callback:ident) => {
+        $callback! {
+            fn CgosLibGetVersion() -> c_uint;
+            fn CgosLibInitialize() -> cgosret_bool;
+            fn CgosLibUninitialize() -> cgosret_bool;
+            fn CgosLibIsAvailable() -> cgosret_bool;
+            fn CgosLibInstall(install: c_uint) -> cgosret_bool;
+            fn CgosLibGetDrvVersion() -> c_uint;
+            fn CgosLibGetLastError() -> c_uint;
+            fn CgosLibSetLastErrorAddress(pErrNo: *mut c_uint) -> cgosret_bool;
+            fn CgosBoardCount(dwClass: c_uint, dwFlags: c_uint) -> c_uint;
+            fn CgosBoardOpen(dwClass: c_uint, dwNum: c_uint, dwFlags: c_uint, phCgos: *mut HCGOS)
+                -> cgosret_bool => [phCgos];
+            fn CgosBoardOpenByNameA(pszName: *const c_char, phCgos: *mut HCGOS) -> cgosret_bool
+                => [phCgos];
+            fn CgosBoardClose(hCgos: HCGOS) -> cgosret_bool;
+            fn CgosBoardGetNameA(hCgos: HCGOS, pszName: *mut c_char, dwSize: c_uint) -> cgosret_bool
+                => [pszName: dwSize];
+            fn CgosBoardGetInfoA(hCgos: HCGOS, pBoardInfo: *mut CGOSBOARDINFOA) -> cgosret_bool
+                => [pBoardInfo];
+            fn CgosBoardGetBootCounter(hCgos: HCGOS, pdwCount: *mut c_uint) -> cgosret_bool
+                => [pdwCount];
+            fn CgosBoardGetRunningTimeMeter(hCgos: HCGOS, pdwCount: *mut c_uint) -> cgosret_bool
+                => [pdwCount];
+            fn CgosBoardGetOption(hCgos: HCGOS, dwOption: c_uint, pdwSetting: *mut c_uint)
+                -> cgosret_bool => [pdwSetting];
+            fn CgosBoardSetOption(hCgos: HCGOS, dwOption: c_uint, dwSetting: c_uint) -> cgosret_bool;
+            fn CgosBoardGetBootErrorLog(
+                hCgos: HCGOS,
+                dwType: c_uint,
+                pdwLogType: *mut c_uint,
+                pBytes: *mut c_uchar,
+                pdwLen: *mut c_uint,
+            ) -> cgosret_bool => [pdwLogType, pBytes: *pdwLen, pdwLen];
+            fn CgosStorageAreaCount(hCgos: HCGOS, dwUnit: c_uint) -> c_uint;
+            fn CgosStorageAreaType(hCgos: HCGOS, dwUnit: c_uint) -> c_uint;
+            fn CgosStorageAreaSize(hCgos: HCGOS, dwUnit: c_uint) -> c_uint;
+            fn CgosStorageAreaBlockSize(hCgos: HCGOS, dwUnit: c_uint) -> c_uint;
+            fn CgosStorageAreaRead(
+                hCgos: HCGOS,
+                dwUnit: c_uint,
+                dwOffset: c_uint,
+                pBytes: *mut c_uchar,
+                dwLen: c_uint,
+            ) -> cgosret_bool => [pBytes: dwLen];
+            fn CgosStorageAreaWrite(
+                hCgos: HCGOS,
+                dwUnit: c_uint,
+                dwOffset: c_uint,
+                pBytes: *mut c_uchar,
+                dwLen: c_uint,
+            ) -> cgosret_bool;
+            fn CgosStorageAreaErase(hCgos: HCGOS, dwUnit: c_uint, dwOffset: c_uint, dwLen: c_uint)
+                -> cgosret_bool;
+            fn CgosStorageAreaEraseStatus(
+                hCgos: HCGOS,
+                dwUnit: c_uint,
+                dwOffset: c_uint,
+                dwLen: c_uint,
+                lpStatus: *mut c_uint,
+            ) -> cgosret_bool => [lpStatus];
+            fn CgosStorageAreaLock(
+                hCgos: HCGOS,
+                dwUnit: c_uint,
+                dwFlags: c_uint,
+                pBytes: *mut c_uchar,
+                dwLen: c_uint,
+            ) -> cgosret_bool;
+            fn CgosStorageAreaUnlock(
+                hCgos: HCGOS,
+                dwUnit: c_uint,
+                dwFlags: c_uint,
+                pBytes: *mut c_uchar,
+                dwLen: c_uint,
+            ) -> cgosret_bool;
+            fn CgosStorageAreaIsLocked(hCgos: HCGOS, dwUnit: c_uint, dwFlags: c_uint) -> cgosret_bool;
+            fn CgosIOCount(hCgos: HCGOS) -> c_uint;
+            fn CgosIOIsAvailable(hCgos: HCGOS, dwUnit: c_uint) -> cgosret_bool;
+            fn CgosIORead(hCgos: HCGOS, dwUnit: c_uint, pdwData: *mut c_uint) -> cgosret_bool
+                => [pdwData];
+            fn CgosIOWrite(hCgos: HCGOS, dwUnit: c_uint, dwData: c_uint) -> cgosret_bool;
+            fn CgosIOXorAndXor(
+                hCgos: HCGOS,
+                dwUnit: c_uint,
+                dwXorMask1: c_uint,
+                dwAndMask: c_uint,
+                dwXorMask2: c_uint,
+            ) -> cgosret_bool;
+            fn CgosIOGetDirectionCaps(
+                hCgos: HCGOS,
+                dwUnit: c_uint,
+                pdwInputs: *mut c_uint,
+                pdwOutputs: *mut c_uint,
+            ) -> cgosret_bool => [pdwInputs, pdwOutputs];
+            fn CgosIOGetDirection(hCgos: HCGOS, dwUnit: c_uint, pdwData: *mut c_uint) -> cgosret_bool
+                => [pdwData];
+            fn CgosIOSetDirection(hCgos: HCGOS, dwUnit: c_uint, dwData: c_uint) -> cgosret_bool;
+            fn CgosIOGetNameA(hCgos: HCGOS, dwUnit: c_uint, pszName: *mut c_char, dwSize: c_uint)
+                -> cgosret_bool => [pszName: dwSize];
+            fn CgosWDogCount(hCgos: HCGOS) -> c_uint;
+            fn CgosWDogIsAvailable(hCgos: HCGOS, dwUnit: c_uint) -> cgosret_bool;
+            fn CgosWDogTrigger(hCgos: HCGOS, dwUnit: c_uint) -> cgosret_bool;
+            fn CgosWDogGetConfigStruct(hCgos: HCGOS, dwUnit: c_uint, pConfig: *mut CGOSWDCONFIG)
+                -> cgosret_bool => [pConfig];
+            fn CgosWDogSetConfigStruct(hCgos: HCGOS, dwUnit: c_uint, pConfig: *mut CGOSWDCONFIG)
+                -> cgosret_bool;
+            fn CgosWDogSetConfig(
+                hCgos: HCGOS,
+                dwUnit: c_uint,
+                timeout: c_uint,
+                delay: c_uint,
+                mode: c_uint,
+            ) -> cgosret_bool;
+            fn CgosWDogDisable(hCgos: HCGOS, dwUnit: c_uint) -> cgosret_bool;
+            fn CgosWDogGetInfo(hCgos: HCGOS, dwUnit: c_uint, pInfo: *mut CGOSWDINFO) -> cgosret_bool
+                => [pInfo];
+            fn CgosTemperatureCount(hCgos: HCGOS) -> c_uint;
+            fn CgosTemperatureGetInfo(hCgos: HCGOS, dwUnit: c_uint, pInfo: *mut CGOSTEMPERATUREINFO)
+                -> cgosret_bool => [pInfo];
+            fn CgosTemperatureGetCurrent(
+                hCgos: HCGOS,
+                dwUnit: c_uint,
+                pdwSetting: *mut c_uint,
+                pdwStatus: *mut c_uint,
+            ) -> cgosret_bool => [pdwSetting, pdwStatus];
+            fn CgosTemperatureSetLimits(
+                hCgos: HCGOS,
+                dwUnit: c_uint,
+                pInfo: *mut CGOSTEMPERATUREINFO,
+            ) -> cgosret_bool;
+            fn CgosFanCount(hCgos: HCGOS) -> c_uint;
+            fn CgosFanGetInfo(hCgos: HCGOS, dwUnit: c_uint, pInfo: *mut CGOSFANINFO) -> cgosret_bool
+                => [pInfo];
+            fn CgosFanGetCurrent(
+                hCgos: HCGOS,
+                dwUnit: c_uint,
+                pdwSetting: *mut c_uint,
+                pdwStatus: *mut c_uint,
+            ) -> cgosret_bool => [pdwSetting, pdwStatus];
+            fn CgosFanSetLimits(hCgos: HCGOS, dwUnit: c_uint, pInfo: *mut CGOSFANINFO) -> cgosret_bool;
+            fn CgosVoltageCount(hCgos: HCGOS) -> c_uint;
+            fn CgosVoltageGetInfo(hCgos: HCGOS, dwUnit: c_uint, pInfo: *mut CGOSVOLTAGEINFO)
+                -> cgosret_bool => [pInfo];
+            fn CgosVoltageGetCurrent(
+                hCgos: HCGOS,
+                dwUnit: c_uint,
+                pdwSetting: *mut c_uint,
+                pdwStatus: *mut c_uint,
+            ) -> cgosret_bool => [pdwSetting, pdwStatus];
+            fn CgosVoltageSetLimits(hCgos: HCGOS, dwUnit: c_uint, pInfo: *mut CGOSVOLTAGEINFO)
+                -> cgosret_bool;
+            fn CgosPerformanceGetCurrent(hCgos: HCGOS, dwUnit: c_uint, pdwSetting: *mut c_uint)
+                -> cgosret_bool => [pdwSetting];
+            fn CgosPerformanceSetCurrent(hCgos: HCGOS, dwUnit: c_uint, dwSetting: c_uint)
+                -> cgosret_bool;
+            fn CgosPerformanceGetPolicyCaps(hCgos: HCGOS, dwUnit: c_uint, pdwSetting: *mut c_uint)
+                -> cgosret_bool => [pdwSetting];
+            fn CgosPerformanceGetPolicy(hCgos: HCGOS, dwUnit: c_uint, pdwSetting: *mut c_uint)
+                -> cgosret_bool => [pdwSetting];
+            fn CgosPerformanceSetPolicy(hCgos: HCGOS, dwUnit: c_uint, dwSetting: c_uint)
+                -> cgosret_bool;
+            // The size of the info structure depends on dwType, it is not captured.
+            fn CgosCgbcGetInfo(hCgos: HCGOS, dwType: c_uint, pInfo: *mut c_void) -> cgosret_bool;
+            fn CgosCgbcSetControl(hCgos: HCGOS, dwLine: c_uint, dwSetting: c_uint) -> cgosret_bool;
+            fn CgosCgbcReadWrite(
+                hCgos: HCGOS,
+                bDataByte: c_uchar,
+                pDataByte: *mut c_uchar,
+                dwClockDelay: c_uint,
+                dwTimeoutDelay: c_uint,
+            ) -> cgosret_bool => [pDataByte];
+            fn CgosCgbcHandleCommand(
+                hCgos: HCGOS,
+                pBytesWrite: *mut c_uchar,
+                dwLenWrite: c_uint,
+                pBytesRead: *mut c_uchar,
+                dwLenRead: c_uint,
+                pdwStatus: *mut c_uint,
+            ) -> cgosret_bool => [pBytesRead: dwLenRead, pdwStatus];
+        }
+    };
+}
+
+#[cfg(any(feature = "capture", all(feature = "dynamic", not(feature = "mock"))))]
+pub(crate) use cgos_functions;
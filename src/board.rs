@@ -1,21 +1,40 @@
 use std::{
-    ffi::{CStr, CString},
+    ffi::{c_char, CString},
+    fmt::{self, Display, Formatter},
     marker::PhantomData,
-    mem::{size_of, zeroed},
+    mem::{forget, size_of, zeroed},
     time::Duration,
 };
 
 use bitflags::bitflags;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
+#[cfg(feature = "io")]
+use crate::io::IoPort;
+#[cfg(feature = "storage")]
+use crate::storage_area::{StorageArea, StorageAreaType, StorageKind};
+#[cfg(feature = "wdog")]
+use crate::watchdog::Watchdog;
 use crate::{
     bindings::{
-        CgosBoardClose, CgosBoardCount, CgosBoardGetBootCounter, CgosBoardGetInfoA,
-        CgosBoardGetNameA, CgosBoardOpen, CgosBoardOpenByNameA, CGOSBOARDINFOA, CGOSTIME,
+        CgosBoardClose, CgosBoardCount, CgosBoardGetBootCounter, CgosBoardGetBootErrorLog,
+        CgosBoardGetInfoA, CgosBoardGetNameA, CgosBoardGetOption, CgosBoardGetRunningTimeMeter,
+        CgosBoardOpen, CgosBoardOpenByNameA, CgosBoardSetOption, CGOSBOARDINFOA, CGOSTIME,
         CGOS_BOARD_CLASS_CPU, CGOS_BOARD_CLASS_IO, CGOS_BOARD_CLASS_VGA,
     },
+    cgbc::Cgbc,
+    device::DeviceClass,
+    diagnostics::BoardDiagnostics,
+    error::{ffi_call, ffi_value, CgosError, Result},
+    performance::Performance,
+};
+#[cfg(feature = "sensors")]
+use crate::{
     fan::Fan,
-    storage_area::{StorageArea, StorageAreaType},
+    sensor::{HealthSnapshot, Sensor},
     temperature::Temperature,
+    voltage::Voltage,
 };
 
 pub const FLAGS: u32 = 0;
@@ -27,107 +46,308 @@ pub struct Board<'library> {
 
 impl<'library> Board<'library> {
     pub(crate) fn amount(class: BoardClass) -> usize {
-        unsafe { CgosBoardCount(class.bits, FLAGS) as usize }
+        ffi_value!(CgosBoardCount(class.bits, FLAGS)) as usize
     }
 
-    pub(crate) fn new(class: BoardClass, index: usize) -> Board<'library> {
+    pub(crate) fn new(class: BoardClass, index: usize) -> Result<Board<'library>> {
+        let amount = Self::amount(class);
+        if index >= amount {
+            return Err(CgosError::IndexOutOfRange { index, amount });
+        }
         let mut handle = Default::default();
-        assert_ne!(
-            unsafe { CgosBoardOpen(class.bits, index.try_into().unwrap(), FLAGS, &mut handle) },
-            0,
-        );
-        Self {
+        ffi_call!(CgosBoardOpen(
+            class.bits,
+            index.try_into().unwrap(),
+            FLAGS,
+            &mut handle
+        ))?;
+        Ok(Self {
             handle,
             _library_lifetime: PhantomData,
-        }
+        })
     }
 
-    pub(crate) fn from_name(name: &str) -> Board<'library> {
-        let name = CString::new(name).unwrap();
+    pub(crate) fn from_name(name: &str) -> Result<Board<'library>> {
+        let name = CString::new(name)?;
         let mut handle = Default::default();
-        assert_ne!(
-            unsafe { CgosBoardOpenByNameA(name.as_ptr(), &mut handle) },
-            0,
-        );
+        ffi_call!(CgosBoardOpenByNameA(name.as_ptr(), &mut handle))?;
+        Ok(Self {
+            handle,
+            _library_lifetime: PhantomData,
+        })
+    }
+
+    /// Board handle to pass to CGOS functions directly, it stays owned by the board.
+    pub fn raw_handle(&self) -> u32 {
+        self.handle
+    }
+
+    /// Takes ownership of a handle opened outside the crate, it is closed when dropped.
+    ///
+    /// # Safety
+    ///
+    /// `handle` has to be an open board handle that is not closed elsewhere.
+    pub unsafe fn from_raw_handle(handle: u32) -> Self {
         Self {
             handle,
             _library_lifetime: PhantomData,
         }
     }
 
-    pub fn name(&self) -> String {
+    /// Releases the handle without closing it.
+    pub fn into_raw_handle(self) -> u32 {
+        let handle = self.handle;
+        forget(self);
+        handle
+    }
+
+    pub fn name(&self) -> Result<String> {
         const SIZE: usize = 128;
-        let mut name = vec![0; SIZE];
-        assert_ne!(
-            unsafe { CgosBoardGetNameA(self.handle, name.as_mut_ptr() as *mut i8, SIZE as u32,) },
-            0,
-        );
-        unsafe { CStr::from_ptr(name.as_ptr()) }
-            .to_str()
-            .unwrap()
-            .to_string()
+        let mut name = [0; SIZE];
+        ffi_call!(CgosBoardGetNameA(
+            self.handle,
+            name.as_mut_ptr(),
+            SIZE as u32
+        ))?;
+        Ok(string_from_c_chars(&name))
     }
 
-    pub fn info(&self) -> BoardInfo {
+    pub fn info(&self) -> Result<BoardInfo> {
         let mut info: CGOSBOARDINFOA = unsafe { zeroed() };
         info.dwSize = size_of::<CGOSBOARDINFOA>() as u32;
-        assert_ne!(unsafe { CgosBoardGetInfoA(self.handle, &mut info) }, 0);
-        info.into()
+        ffi_call!(CgosBoardGetInfoA(self.handle, &mut info))?;
+        Ok(info.into())
     }
 
-    pub fn boot_count(&self) -> usize {
+    pub fn boot_count(&self) -> Result<usize> {
         let mut count = 0;
-        assert_ne!(
-            unsafe { CgosBoardGetBootCounter(self.handle, &mut count) },
-            0,
-        );
-        count as usize
+        ffi_call!(CgosBoardGetBootCounter(self.handle, &mut count))?;
+        Ok(count as usize)
     }
 
-    pub fn running_time(&self) -> Duration {
+    /// Total time the board has been powered on, the meter counts in full hours.
+    pub fn running_time(&self) -> Result<Duration> {
         let mut hours = 0;
-        assert_ne!(
-            unsafe { CgosBoardGetBootCounter(self.handle, &mut hours) },
+        ffi_call!(CgosBoardGetRunningTimeMeter(self.handle, &mut hours))?;
+        Ok(Duration::from_secs(hours as u64 * 60 * 60))
+    }
+
+    pub fn boot_error_log(&self) -> Result<BootErrorLog> {
+        const SIZE: usize = 4096;
+        let mut data = vec![0; SIZE];
+        let mut length = SIZE as u32;
+        let mut log_type = 0;
+        ffi_call!(CgosBoardGetBootErrorLog(
+            self.handle,
             0,
-        );
-        Duration::from_secs(hours as u64 * 60 * 60)
+            &mut log_type,
+            data.as_mut_ptr(),
+            &mut length,
+        ))?;
+        data.truncate(length as usize);
+        Ok(BootErrorLog { log_type, data })
+    }
+
+    pub fn option(&self, option: BoardOption) -> Result<u32> {
+        let mut setting = 0;
+        ffi_call!(CgosBoardGetOption(self.handle, option.0, &mut setting))?;
+        Ok(setting)
+    }
+
+    /// Whether the board implements `option`, probed by reading it.
+    ///
+    /// CGOS reports no valid ranges for options, so this is all that can be checked before
+    /// `set_option()`.
+    pub fn option_supported(&self, option: BoardOption) -> bool {
+        self.option(option).is_ok()
+    }
+
+    pub fn set_option(&self, option: BoardOption, setting: u32) -> Result<()> {
+        ffi_call!(CgosBoardSetOption(self.handle, option.0, setting))
+    }
+
+    pub fn diagnostics(&'library self) -> BoardDiagnostics {
+        BoardDiagnostics::collect(self)
+    }
+
+    /// Opens every device of a class in turn, e.g. `board.devices::<Watchdog>()`.
+    pub fn devices<Device: DeviceClass<'library>>(
+        &'library self,
+    ) -> impl Iterator<Item = Result<Device>> + 'library {
+        (0..Device::amount(self)).map(move |index| Device::open(self, index))
     }
 
+    pub fn get_cgbc(&'library self) -> Cgbc<'library> {
+        Cgbc::new(self.handle)
+    }
+
+    #[cfg(feature = "sensors")]
     pub fn get_number_of_temperatures(&self) -> usize {
         Temperature::amount(self.handle)
     }
 
-    pub fn get_temperature(&'library self, index: usize) -> Temperature<'library> {
+    #[cfg(feature = "sensors")]
+    pub fn get_temperature(&'library self, index: usize) -> Result<Temperature<'library>> {
         Temperature::new(self.handle, index)
     }
 
+    #[cfg(feature = "sensors")]
+    pub fn temperatures(
+        &'library self,
+    ) -> impl Iterator<Item = Result<Temperature<'library>>> + 'library {
+        self.devices()
+    }
+
+    #[cfg(feature = "sensors")]
     pub fn get_number_of_fans(&self) -> usize {
         Fan::amount(self.handle)
     }
 
-    pub fn get_fan(&'library self, index: usize) -> Fan<'library> {
+    #[cfg(feature = "sensors")]
+    pub fn get_fan(&'library self, index: usize) -> Result<Fan<'library>> {
         Fan::new(self.handle, index)
     }
 
+    #[cfg(feature = "sensors")]
+    pub fn fans(&'library self) -> impl Iterator<Item = Result<Fan<'library>>> + 'library {
+        self.devices()
+    }
+
+    #[cfg(feature = "sensors")]
+    pub fn get_number_of_voltages(&self) -> usize {
+        Voltage::amount(self.handle)
+    }
+
+    #[cfg(feature = "sensors")]
+    pub fn get_voltage(&'library self, index: usize) -> Result<Voltage<'library>> {
+        Voltage::new(self.handle, index)
+    }
+
+    #[cfg(feature = "sensors")]
+    pub fn voltages(&'library self) -> impl Iterator<Item = Result<Voltage<'library>>> + 'library {
+        self.devices()
+    }
+
+    /// Iterates over all temperature, fan and voltage sensors.
+    #[cfg(feature = "sensors")]
+    pub fn sensors(&'library self) -> impl Iterator<Item = Sensor<'library>> + 'library {
+        let temperatures = (0..self.get_number_of_temperatures())
+            .filter_map(|index| self.get_temperature(index).ok())
+            .map(Sensor::Temperature);
+        let fans = (0..self.get_number_of_fans())
+            .filter_map(|index| self.get_fan(index).ok())
+            .map(Sensor::Fan);
+        let voltages = (0..self.get_number_of_voltages())
+            .filter_map(|index| self.get_voltage(index).ok())
+            .map(Sensor::Voltage);
+        temperatures.chain(fans).chain(voltages)
+    }
+
+    #[cfg(feature = "sensors")]
+    pub fn health_snapshot(&'library self) -> HealthSnapshot {
+        HealthSnapshot {
+            temperatures: (0..self.get_number_of_temperatures())
+                .map(|index| {
+                    self.get_temperature(index)
+                        .and_then(|sensor| sensor.current())
+                        .ok()
+                })
+                .collect(),
+            fans: (0..self.get_number_of_fans())
+                .map(|index| self.get_fan(index).and_then(|sensor| sensor.current()).ok())
+                .collect(),
+            voltages: (0..self.get_number_of_voltages())
+                .map(|index| {
+                    self.get_voltage(index)
+                        .and_then(|sensor| sensor.current())
+                        .ok()
+                })
+                .collect(),
+        }
+    }
+
+    /// CGOS provides no count of performance units, so `unit` is not validated.
+    pub fn get_performance(&'library self, unit: u32) -> Performance<'library> {
+        Performance::new(self.handle, unit)
+    }
+
+    #[cfg(feature = "io")]
+    pub fn get_number_of_io_ports(&self) -> usize {
+        IoPort::amount(self.handle)
+    }
+
+    #[cfg(feature = "io")]
+    pub fn get_io_port(&'library self, index: usize) -> Result<IoPort<'library>> {
+        IoPort::new(self.handle, index)
+    }
+
+    #[cfg(feature = "io")]
+    pub fn io_ports(&'library self) -> impl Iterator<Item = Result<IoPort<'library>>> + 'library {
+        self.devices()
+    }
+
+    #[cfg(feature = "storage")]
     pub fn get_number_of_storage_areas(&self, type_: StorageAreaType) -> usize {
         StorageArea::amount(self.handle, type_)
     }
 
-    pub fn get_storage_area_from_index(&'library self, index: usize) -> StorageArea<'library> {
+    #[cfg(feature = "storage")]
+    pub fn get_storage_area_from_index(
+        &'library self,
+        index: usize,
+    ) -> Result<StorageArea<'library>> {
         StorageArea::from_index(self.handle, index)
     }
 
+    #[cfg(feature = "storage")]
+    pub fn storage_areas(
+        &'library self,
+    ) -> impl Iterator<Item = Result<StorageArea<'library>>> + 'library {
+        self.devices()
+    }
+
+    #[cfg(feature = "storage")]
     pub fn get_storage_area_from_type(
         &'library self,
         type_: StorageAreaType,
     ) -> StorageArea<'library> {
         StorageArea::from_type(self.handle, type_)
     }
+
+    /// Returns the first area of the given kind, without having to scan indices.
+    #[cfg(feature = "storage")]
+    pub fn get_storage_area_from_kind(
+        &'library self,
+        kind: StorageKind,
+    ) -> Result<StorageArea<'library>> {
+        StorageArea::from_kind(self.handle, kind)
+    }
+
+    #[cfg(feature = "wdog")]
+    pub fn get_number_of_watchdogs(&self) -> usize {
+        Watchdog::amount(self.handle)
+    }
+
+    #[cfg(feature = "wdog")]
+    pub fn get_watchdog(&'library self, index: usize) -> Result<Watchdog<'library>> {
+        Watchdog::new(self.handle, index)
+    }
+
+    #[cfg(feature = "wdog")]
+    pub fn watchdogs(
+        &'library self,
+    ) -> impl Iterator<Item = Result<Watchdog<'library>>> + 'library {
+        self.devices()
+    }
 }
 
 impl<'library> Drop for Board<'library> {
     fn drop(&mut self) {
-        assert_ne!(unsafe { CgosBoardClose(self.handle) }, 0);
+        // Fails for handles that died with a driver reload, there is nothing left to release then.
+        if ffi_value!(CgosBoardClose(self.handle)) == 0 {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(handle = self.handle, "closing CGOS board failed");
+        }
     }
 }
 
@@ -140,6 +360,54 @@ bitflags! {
     }
 }
 
+impl Display for BoardClass {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        let classes = [
+            (BoardClass::CPU, "CPU"),
+            (BoardClass::VGA, "VGA"),
+            (BoardClass::IO, "IO"),
+        ];
+        let names: Vec<_> = classes
+            .iter()
+            .filter(|(class, _)| self.contains(*class))
+            .map(|(_, name)| *name)
+            .collect();
+        match names.is_empty() {
+            true => write!(formatter, "none"),
+            false => write!(formatter, "{}", names.join(", ")),
+        }
+    }
+}
+
+/// Serialized as the raw CGOS bits.
+#[cfg(feature = "serde")]
+impl Serialize for BoardClass {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        self.bits.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for BoardClass {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        u32::deserialize(deserializer).map(Self::from_bits_truncate)
+    }
+}
+
+/// Boot error log as stored by the firmware, its layout depends on `log_type` and the board.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BootErrorLog {
+    pub log_type: u32,
+    pub data: Vec<u8>,
+}
+
+/// Firmware option identifier as documented for the board, CGOS itself defines none.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BoardOption(pub u32);
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Clone, Debug)]
 pub struct BoardInfo {
     pub board: String,
@@ -151,6 +419,7 @@ pub struct BoardInfo {
     pub serial_number: String,
     pub product_revision: String,
     pub system_bios_revision: u16,
+    pub bios_interface_revision: u16,
     pub bios_interface_build_revision: u16,
     pub classes: BoardClass,
     pub primary_class: BoardClass,
@@ -161,55 +430,47 @@ pub struct BoardInfo {
 
 impl From<CGOSBOARDINFOA> for BoardInfo {
     fn from(info: CGOSBOARDINFOA) -> Self {
-        let board = unsafe { CStr::from_ptr(info.szBoard.as_ptr()) }
-            .to_str()
-            .unwrap()
-            .to_string();
-        let board_sub = unsafe { CStr::from_ptr(info.szBoardSub.as_ptr()) }
-            .to_str()
-            .unwrap()
-            .to_string();
-        let manufacturer = unsafe { CStr::from_ptr(info.szManufacturer.as_ptr()) }
-            .to_str()
-            .unwrap()
-            .to_string();
-        let serial_number = unsafe { CStr::from_ptr(info.szSerialNumber.as_ptr()) }
-            .to_str()
-            .unwrap()
-            .to_string();
         let product_revision = {
             let major = ((info.wProductRevision & 0xff00) >> 8) as u8 as char;
             let minor = (info.wProductRevision & 0xff) as u8 as char;
             format!("{major}.{minor}")
         };
-        let part_number = unsafe { CStr::from_ptr(info.szPartNumber.as_ptr()) }
-            .to_str()
-            .unwrap()
-            .to_string();
-        let european_article_number = unsafe { CStr::from_ptr(info.szEAN.as_ptr()) }
-            .to_str()
-            .unwrap()
-            .to_string();
         Self {
-            board,
-            board_sub,
-            manufacturer,
+            board: string_from_c_chars(&info.szBoard),
+            board_sub: string_from_c_chars(&info.szBoardSub),
+            manufacturer: string_from_c_chars(&info.szManufacturer),
             manufacturer_sub: info.dwManufacturer,
             manufacturing_date: info.stManufacturingDate.into(),
             last_repair_date: info.stLastRepairDate.into(),
-            serial_number,
+            serial_number: string_from_c_chars(&info.szSerialNumber),
             product_revision,
-            system_bios_revision: info.wBiosInterfaceRevision,
+            system_bios_revision: info.wSystemBiosRevision,
+            bios_interface_revision: info.wBiosInterfaceRevision,
             bios_interface_build_revision: info.wBiosInterfaceBuildRevision,
             classes: BoardClass::from_bits_truncate(info.dwClasses),
             primary_class: BoardClass::from_bits_truncate(info.dwPrimaryClass),
             repair_counter: info.dwRepairCounter,
-            part_number,
-            european_article_number,
+            part_number: string_from_c_chars(&info.szPartNumber),
+            european_article_number: string_from_c_chars(&info.szEAN),
         }
     }
 }
 
+impl Display for BoardInfo {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "{} {} rev. {}, serial {}, BIOS {:#x}",
+            self.manufacturer,
+            self.board,
+            self.product_revision,
+            self.serial_number,
+            self.system_bios_revision
+        )
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Copy, Clone, Debug)]
 pub struct BoardTime {
     pub year: u16,
@@ -236,3 +497,76 @@ impl From<CGOSTIME> for BoardTime {
         }
     }
 }
+
+impl Display for BoardTime {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        if !self.is_set() {
+            return write!(formatter, "not set");
+        }
+        write!(
+            formatter,
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )
+    }
+}
+
+impl BoardTime {
+    /// Boards without a recorded date report all zeros.
+    pub fn is_set(&self) -> bool {
+        self.year != 0
+    }
+
+    #[cfg(feature = "chrono")]
+    pub fn to_naive_date_time(&self) -> Option<chrono::NaiveDateTime> {
+        chrono::NaiveDate::from_ymd_opt(self.year.into(), self.month.into(), self.day.into())?
+            .and_hms_milli_opt(
+                self.hour.into(),
+                self.minute.into(),
+                self.second.into(),
+                self.millisecond.into(),
+            )
+    }
+}
+
+/// Decodes a fixed size C string field, which is not nul terminated if it is completely filled.
+pub(crate) fn string_from_c_chars(chars: &[c_char]) -> String {
+    let bytes: Vec<_> = chars
+        .iter()
+        .map(|&char| char as u8)
+        .take_while(|&byte| byte != 0)
+        .collect();
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::{
+        congatec::Congatec,
+        mock::{install, test_lock, MockBoard},
+    };
+
+    #[test]
+    fn only_readable_options_are_supported() {
+        let _lock = test_lock();
+        install(vec![MockBoard {
+            options: BTreeMap::from([(1, 3)]),
+            ..Default::default()
+        }]);
+        let library = Congatec::new().unwrap();
+        let board = library.get_board(BoardClass::ALL, 0).unwrap();
+        assert!(board.option_supported(BoardOption(1)));
+        assert_eq!(board.option(BoardOption(1)).unwrap(), 3);
+        assert!(!board.option_supported(BoardOption(2)));
+        assert!(matches!(
+            board.option(BoardOption(2)),
+            Err(CgosError::Failed {
+                function: "CgosBoardGetOption",
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,291 @@
+//! Recording of libcgos calls and their results, and replaying them without hardware.
+//!
+//! [`record`] appends one line per call to a file, [`replay`] answers each call from such a file
+//! instead of the library. Combined with the `mock` feature, traces captured on a real board
+//! replay on machines without libcgos. Replaying expects the same sequence of calls as recorded,
+//! so traces of applications polling from several threads may not replay.
+
+use std::{
+    collections::VecDeque,
+    fmt::Write as _,
+    fs::{read_to_string, File},
+    io::Write,
+    path::Path,
+    ptr::copy_nonoverlapping,
+    sync::{Mutex, MutexGuard, PoisonError},
+};
+
+use crate::error::{CgosError, Result};
+
+enum Mode {
+    PassThrough,
+    Record(File),
+    Replay(VecDeque<Call>),
+    /// Replay reached this call, which the trace doesn't continue with.
+    Diverged(String),
+}
+
+struct Call {
+    call: String,
+    return_value: u32,
+    outputs: Vec<Vec<u8>>,
+}
+
+static MODE: Mutex<Mode> = Mutex::new(Mode::PassThrough);
+
+fn mode() -> MutexGuard<'static, Mode> {
+    MODE.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Records all following calls to a new file at `path`, replacing a previous recording or replay.
+pub fn record(path: impl AsRef<Path>) -> Result<()> {
+    *mode() = Mode::Record(File::create(path)?);
+    Ok(())
+}
+
+/// Answers all following calls from the trace at `path` instead of calling libcgos.
+///
+/// Once a call differs from the recorded ones or the trace is exhausted, it and all following
+/// calls fail, with [`CgosError::ReplayDiverged`] naming the first of them.
+pub fn replay(path: impl AsRef<Path>) -> Result<()> {
+    let trace = read_to_string(path)?;
+    let calls = trace
+        .lines()
+        .enumerate()
+        .map(|(index, line)| parse(line).ok_or(CgosError::InvalidTrace { line: index + 1 }))
+        .collect::<Result<_>>()?;
+    *mode() = Mode::Replay(calls);
+    Ok(())
+}
+
+/// Stops recording or replaying, calls go to libcgos again.
+pub fn stop() {
+    *mode() = Mode::PassThrough;
+}
+
+/// Lines look like `CgosIORead(1, 0, *) = 1 0f000000`, pointer arguments are written as `*` and
+/// the return value is followed by the hex encoded data the call wrote to them, `-` if none.
+fn parse(line: &str) -> Option<Call> {
+    let (call, results) = line.rsplit_once(" = ")?;
+    let mut results = results.split(' ');
+    let return_value = results.next()?.parse().ok()?;
+    let outputs = results
+        .map(|output| match output {
+            "-" => Some(Vec::new()),
+            _ => (0..output.len())
+                .step_by(2)
+                .map(|index| u8::from_str_radix(output.get(index..index + 2)?, 16).ok())
+                .collect(),
+        })
+        .collect::<Option<_>>()?;
+    Some(Call {
+        call: call.to_string(),
+        return_value,
+        outputs,
+    })
+}
+
+/// Returns the recorded result of `call` while replaying, a failure once replay diverged.
+fn replayed(call: &str) -> Option<Call> {
+    let mut mode = mode();
+    let recorded = match &mut *mode {
+        Mode::Replay(calls) => calls.pop_front().filter(|recorded| recorded.call == call),
+        Mode::Diverged(_) => None,
+        _ => return None,
+    };
+    Some(recorded.unwrap_or_else(|| {
+        if let Mode::Replay(_) = *mode {
+            #[cfg(feature = "tracing")]
+            tracing::error!(call, "CGOS call diverged from the replayed trace");
+            *mode = Mode::Diverged(call.to_string());
+        }
+        Call {
+            call: call.to_string(),
+            return_value: 0,
+            outputs: Vec::new(),
+        }
+    }))
+}
+
+/// First call that diverged from the replayed trace.
+pub(crate) fn diverged() -> Option<String> {
+    match &*mode() {
+        Mode::Diverged(call) => Some(call.clone()),
+        _ => None,
+    }
+}
+
+fn recorded(call: &str, return_value: u32, outputs: &[&[u8]]) {
+    let mut mode = mode();
+    let Mode::Record(file) = &mut *mode else {
+        return;
+    };
+    let mut line = format!("{call} = {return_value}");
+    for output in outputs {
+        line.push(' ');
+        if output.is_empty() {
+            line.push('-');
+        }
+        for byte in output.iter() {
+            write!(line, "{byte:02x}").unwrap();
+        }
+    }
+    line.push('\n');
+    // A failing trace file must not change what the application sees from libcgos.
+    let _ = file.write_all(line.as_bytes());
+}
+
+/// Copies recorded output data to `pointer`, at most `length` bytes.
+unsafe fn restore(pointer: *mut u8, length: usize, data: Option<&Vec<u8>>) {
+    if let Some(data) = data {
+        copy_nonoverlapping(data.as_ptr(), pointer, data.len().min(length));
+    }
+}
+
+trait Argument {
+    fn trace(&self) -> String;
+}
+
+impl Argument for u32 {
+    fn trace(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl Argument for u8 {
+    fn trace(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl<T> Argument for *mut T {
+    fn trace(&self) -> String {
+        "*".to_string()
+    }
+}
+
+impl<T> Argument for *const T {
+    fn trace(&self) -> String {
+        "*".to_string()
+    }
+}
+
+/// Bytes written through an output pointer, the whole pointee unless given.
+macro_rules! output_length {
+    ($output:ident) => {
+        std::mem::size_of_val(&*$output)
+    };
+    ($output:ident, $length:expr) => {
+        $length as usize
+    };
+}
+
+/// Wraps each backend function, recording or replaying its return value and the data written to
+/// the listed output pointers.
+macro_rules! captured {
+    ($(
+        fn $function:ident($($argument:ident: $type:ty),* $(,)?) -> $return:ty
+            $(=> [$($output:ident $(: $length:expr)?),*])?;
+    )*) => {$(
+        pub unsafe fn $function($($argument: $type),*) -> $return {
+            let arguments: Vec<String> = vec![$(super::Argument::trace(&$argument)),*];
+            let call = format!("{}({})", stringify!($function), arguments.join(", "));
+            if let Some(recorded) = super::replayed(&call) {
+                let mut _outputs = recorded.outputs.iter();
+                $($(super::restore(
+                    $output as *mut u8,
+                    output_length!($output $(, $length)?),
+                    _outputs.next(),
+                );)*)?
+                return recorded.return_value;
+            }
+            let return_value = crate::bindings::backend::$function($($argument),*);
+            let outputs: &[&[u8]] = &[$($(std::slice::from_raw_parts(
+                $output as *const u8,
+                output_length!($output $(, $length)?),
+            )),*)?];
+            super::recorded(&call, return_value, outputs);
+            return_value
+        }
+    )*};
+}
+
+pub(crate) mod ffi {
+    #![allow(dead_code)]
+    #![allow(non_snake_case)]
+    #![allow(clippy::missing_safety_doc)]
+
+    use std::ffi::{c_char, c_uchar, c_uint, c_void};
+
+    pub use crate::bindings::backend::*;
+    use crate::bindings::cgos_functions;
+
+    cgos_functions!(captured);
+}
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+    use std::{env::temp_dir, fs::write, process};
+
+    use super::*;
+    use crate::{
+        board::BoardClass,
+        congatec::Congatec,
+        mock::{install, test_lock, MockBoard},
+    };
+
+    /// Stops capturing even if the test fails, other tests must see the mock again.
+    struct Stop;
+
+    impl Drop for Stop {
+        fn drop(&mut self) {
+            stop();
+        }
+    }
+
+    #[test]
+    fn lines_hold_call_return_value_and_outputs() {
+        let call = parse("CgosIORead(1, 0, *) = 1 0f000000").unwrap();
+        assert_eq!(call.call, "CgosIORead(1, 0, *)");
+        assert_eq!(call.return_value, 1);
+        assert_eq!(call.outputs, [vec![0x0f, 0, 0, 0]]);
+        let call = parse("CgosBoardGetOption(1, 2, *) = 0 -").unwrap();
+        assert_eq!(call.return_value, 0);
+        assert_eq!(call.outputs, [Vec::<u8>::new()]);
+        assert!(parse("CgosLibInitialize()").is_none());
+        assert!(parse("CgosIORead(1, 0, *) = 1 0g").is_none());
+        assert!(parse("CgosIORead(1, 0, *) = 1 0").is_none());
+    }
+
+    #[test]
+    fn recorded_calls_replay_without_the_board() {
+        let _lock = test_lock();
+        let _stop = Stop;
+        let path = temp_dir().join(format!("cgos-capture-{}.trace", process::id()));
+        install(vec![MockBoard::default()]);
+        record(&path).unwrap();
+        {
+            let library = Congatec::new().unwrap();
+            let board = library.get_board(BoardClass::ALL, 0).unwrap();
+            assert_eq!(board.name().unwrap(), "MOCK");
+        }
+        stop();
+        install(Vec::new());
+        replay(&path).unwrap();
+        {
+            let library = Congatec::new().unwrap();
+            let board = library.get_board(BoardClass::ALL, 0).unwrap();
+            assert_eq!(board.name().unwrap(), "MOCK");
+            let Err(CgosError::ReplayDiverged { call }) = board.boot_count() else {
+                panic!("call after the end of the trace did not fail");
+            };
+            assert!(call.starts_with("CgosBoardGetBootCounter"));
+        }
+        write(&path, "CgosLibInitialize() = 1\nCgosLibInitialize()\n").unwrap();
+        assert!(matches!(
+            replay(&path),
+            Err(CgosError::InvalidTrace { line: 2 })
+        ));
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -0,0 +1,152 @@
+use std::{ffi::c_void, marker::PhantomData};
+
+use crate::{
+    bindings::{CgosCgbcGetInfo, CgosCgbcHandleCommand, CgosCgbcReadWrite, CgosCgbcSetControl},
+    error::{ffi_call, CgosError, Result},
+};
+
+/// Access to the congatec board controller (CGBC) for features not covered by the other modules.
+pub struct Cgbc<'library> {
+    handle: u32,
+    _library_lifetime: PhantomData<&'library ()>,
+}
+
+impl<'library> Cgbc<'library> {
+    pub(crate) fn new(handle: u32) -> Cgbc<'library> {
+        Self {
+            handle,
+            _library_lifetime: PhantomData,
+        }
+    }
+
+    /// Board handle to pass to CGOS functions directly.
+    pub fn raw_handle(&self) -> u32 {
+        self.handle
+    }
+
+    /// # Safety
+    ///
+    /// `handle` has to stay open for `'library`.
+    pub unsafe fn from_raw_handle(handle: u32) -> Self {
+        Self::new(handle)
+    }
+
+    /// Fills `info` with the controller info structure selected by `type_`.
+    ///
+    /// # Safety
+    ///
+    /// `info` has to be at least as large as the structure the firmware writes for `type_`.
+    pub unsafe fn info(&self, type_: u32, info: &mut [u8]) -> Result<()> {
+        ffi_call!(CgosCgbcGetInfo(
+            self.handle,
+            type_,
+            info.as_mut_ptr() as *mut c_void
+        ); type_, length = info.len())
+    }
+
+    /// # Safety
+    ///
+    /// Raw transfers can desynchronize the controller protocol or change firmware state, which the
+    /// rest of the system (including other CGOS calls) relies on.
+    pub unsafe fn dangerous_raw_access(&self) -> CgbcRawAccess<'_, 'library> {
+        CgbcRawAccess {
+            cgbc: self,
+            clock_delay: 0,
+            timeout_delay: 0,
+        }
+    }
+
+    pub fn set_control(&self, line: u32, setting: u32) -> Result<()> {
+        ffi_call!(CgosCgbcSetControl(self.handle, line, setting); line, setting)
+    }
+
+    /// Sends a raw controller command and reads `response_length` bytes of response.
+    pub fn handle_command(
+        &self,
+        request: &[u8],
+        response_length: usize,
+    ) -> Result<CommandResponse> {
+        let request_length = length(request.len())?;
+        let data_length = length(response_length)?;
+        let mut request = request.to_vec();
+        let mut data = vec![0; response_length];
+        let mut status = 0;
+        ffi_call!(CgosCgbcHandleCommand(
+            self.handle,
+            request.as_mut_ptr(),
+            request_length,
+            data.as_mut_ptr(),
+            data_length,
+            &mut status,
+        ); length = request.len(), response_length)?;
+        Ok(CommandResponse { data, status })
+    }
+}
+
+fn length(length: usize) -> Result<u32> {
+    length
+        .try_into()
+        .map_err(|_| CgosError::TooLarge { length })
+}
+
+/// Byte level access to the controller interface, bypassing the command protocol.
+pub struct CgbcRawAccess<'cgbc, 'library> {
+    cgbc: &'cgbc Cgbc<'library>,
+    clock_delay: u32,
+    timeout_delay: u32,
+}
+
+impl<'cgbc, 'library> CgbcRawAccess<'cgbc, 'library> {
+    /// Delays passed to CGOS for each transferred byte, both default to 0.
+    pub fn set_delays(&mut self, clock_delay: u32, timeout_delay: u32) {
+        self.clock_delay = clock_delay;
+        self.timeout_delay = timeout_delay;
+    }
+
+    /// Shifts out every byte of `data` and returns the bytes clocked in at the same time.
+    pub fn read_write(&self, data: &[u8]) -> Result<Vec<u8>> {
+        data.iter()
+            .map(|&byte| {
+                let mut received = 0;
+                ffi_call!(CgosCgbcReadWrite(
+                    self.cgbc.handle,
+                    byte,
+                    &mut received,
+                    self.clock_delay,
+                    self.timeout_delay,
+                ); byte)?;
+                Ok(received)
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommandResponse {
+    pub data: Vec<u8>,
+    /// Controller status byte(s) as returned by CGOS.
+    pub status: u32,
+}
+
+#[cfg(all(test, feature = "mock", target_pointer_width = "64"))]
+mod tests {
+    use super::*;
+    use crate::{
+        board::BoardClass,
+        congatec::Congatec,
+        mock::{install, test_lock, MockBoard},
+    };
+
+    #[test]
+    fn oversized_response_is_rejected() {
+        let _lock = test_lock();
+        install(vec![MockBoard::default()]);
+        let library = Congatec::new().unwrap();
+        let board = library.get_board(BoardClass::ALL, 0).unwrap();
+        let length = u32::MAX as usize + 1;
+        assert!(matches!(
+            board.get_cgbc().handle_command(&[0], length),
+            Err(CgosError::TooLarge { length: rejected }) if rejected == length
+        ));
+    }
+}
@@ -0,0 +1,119 @@
+use std::marker::PhantomData;
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    error::{CgosError, Result},
+    storage_area::StorageArea,
+};
+
+/// Persists one serializable configuration value in a storage area.
+///
+/// The value is bincode encoded behind a schema version and stored with
+/// [`StorageArea::write_atomic`], so a write torn by power loss falls back to the previous
+/// configuration.
+pub struct ConfigStore<'area, 'library, T> {
+    area: &'area StorageArea<'library>,
+    version: u32,
+    _config: PhantomData<T>,
+}
+
+impl<'area, 'library, T> ConfigStore<'area, 'library, T>
+where
+    T: Serialize + DeserializeOwned,
+{
+    /// `version` identifies the schema of `T`, records written with another version are rejected.
+    pub fn new(area: &'area StorageArea<'library>, version: u32) -> Self {
+        Self {
+            area,
+            version,
+            _config: PhantomData,
+        }
+    }
+
+    /// Returns the most recently saved configuration, `None` if nothing valid is stored.
+    pub fn load(&self) -> Result<Option<T>> {
+        let Some(data) = self.area.read_atomic()? else {
+            return Ok(None);
+        };
+        let Some(version) = data.get(..4) else {
+            return Err(CgosError::InvalidFormat);
+        };
+        let version = u32::from_le_bytes(version.try_into().unwrap());
+        if version != self.version {
+            return Err(CgosError::VersionMismatch {
+                found: version,
+                expected: self.version,
+            });
+        }
+        Ok(Some(bincode::deserialize(&data[4..])?))
+    }
+
+    /// Replaces the stored configuration with `config`.
+    ///
+    /// Fails with [`CgosError::StorageFull`] if the encoded value does not fit into half of the
+    /// area, the previously saved configuration is kept then.
+    pub fn save(&self, config: &T) -> Result<()> {
+        let mut data = self.version.to_le_bytes().to_vec();
+        data.extend(bincode::serialize(config)?);
+        self.area.write_atomic(&data)
+    }
+}
+
+#[cfg(all(test, feature = "mock", feature = "config-store"))]
+mod tests {
+    use super::*;
+    use crate::{
+        board::BoardClass,
+        congatec::Congatec,
+        mock::{install, test_lock, MockBoard, MockStorageArea},
+        storage_area::StorageKind,
+    };
+
+    type Config = (u32, String);
+
+    fn install_area() {
+        install(vec![MockBoard {
+            storage_areas: vec![MockStorageArea::new(StorageKind::Eeprom, 128)],
+            ..Default::default()
+        }]);
+    }
+
+    #[test]
+    fn saved_config_loads_back() {
+        let _lock = test_lock();
+        install_area();
+        let library = Congatec::new().unwrap();
+        let board = library.get_board(BoardClass::ALL, 0).unwrap();
+        let area = board.get_storage_area_from_index(0).unwrap();
+        let store = ConfigStore::<Config>::new(&area, 1);
+        assert!(store.load().unwrap().is_none());
+        store.save(&(7, "first".to_string())).unwrap();
+        store.save(&(8, "second".to_string())).unwrap();
+        assert_eq!(store.load().unwrap(), Some((8, "second".to_string())));
+        assert!(matches!(
+            store.save(&(9, "x".repeat(64))),
+            Err(CgosError::StorageFull)
+        ));
+        assert_eq!(store.load().unwrap(), Some((8, "second".to_string())));
+    }
+
+    #[test]
+    fn other_version_is_rejected() {
+        let _lock = test_lock();
+        install_area();
+        let library = Congatec::new().unwrap();
+        let board = library.get_board(BoardClass::ALL, 0).unwrap();
+        let area = board.get_storage_area_from_index(0).unwrap();
+        ConfigStore::<Config>::new(&area, 1)
+            .save(&(7, "first".to_string()))
+            .unwrap();
+        assert!(matches!(
+            ConfigStore::<Config>::new(&area, 2).load(),
+            Err(CgosError::VersionMismatch {
+                found: 1,
+                expected: 2,
+            })
+        ));
+    }
+}
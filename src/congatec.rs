@@ -1,31 +1,380 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    thread::sleep,
+    time::Duration,
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(all(feature = "dynamic", not(feature = "mock")))]
+use crate::dynamic::is_exported;
 use crate::{
-    bindings::{CgosLibInitialize, CgosLibUninitialize},
+    bindings::{
+        CgosLibGetDrvVersion, CgosLibGetVersion, CgosLibInitialize, CgosLibInstall,
+        CgosLibIsAvailable, CgosLibUninitialize,
+    },
     board::{Board, BoardClass},
+    diagnostics::Diagnostics,
+    error::{ffi_call, ffi_value, lock, CgosError, ErrorCode, Result},
+    self_test::SelfTestReport,
 };
 
-pub struct Congatec {}
+/// Handle to libcgos, boards and devices are opened through it.
+///
+/// All CGOS calls of the crate are serialized through one process-wide lock, so the library and
+/// every device can be shared between and moved across threads.
+pub struct Congatec {
+    initialized: bool,
+    board_class: BoardClass,
+}
 
 impl Congatec {
-    pub fn new() -> Self {
-        assert_ne!(unsafe { CgosLibInitialize() }, 0);
-        Self {}
+    pub fn new() -> Result<Self> {
+        Self::builder().open()
+    }
+
+    pub fn builder() -> CongatecBuilder {
+        CongatecBuilder::default()
+    }
+
+    /// Installs the CGOS kernel driver, needs administrative privileges and no open library.
+    pub fn install_driver() -> Result<()> {
+        install(true)
+    }
+
+    pub fn uninstall_driver() -> Result<()> {
+        install(false)
+    }
+
+    /// Version of the libcgos user space library.
+    pub fn version(&self) -> Version {
+        ffi_value!(CgosLibGetVersion()).into()
+    }
+
+    /// Version of the CGOS kernel driver libcgos talks to.
+    pub fn driver_version(&self) -> Version {
+        ffi_value!(CgosLibGetDrvVersion()).into()
+    }
+
+    /// Whether libcgos and its driver support `feature`.
+    ///
+    /// With the `dynamic` feature this checks that the loaded library exports all functions of
+    /// the feature, a linked library always has them. Either way the driver has to be loaded.
+    pub fn capability(&self, feature: Feature) -> bool {
+        feature
+            .functions()
+            .iter()
+            .all(|function| is_exported(function))
+            && self.driver_version() != Version::from(0)
+    }
+
+    /// Collects versions and the state of every board into one report, e.g. for support requests.
+    pub fn diagnostics(&self) -> Diagnostics {
+        Diagnostics::collect(self)
+    }
+
+    /// Exercises every subsystem of every board without changing any setting, e.g. on test
+    /// stations.
+    pub fn self_test(&self) -> SelfTestReport {
+        SelfTestReport::run(self)
     }
 
     pub fn get_number_of_boards(&self, class: BoardClass) -> usize {
         Board::amount(class)
     }
 
-    pub fn get_board<'library>(&'library self, class: BoardClass, index: usize) -> Board<'library> {
+    pub fn get_board<'library>(
+        &'library self,
+        class: BoardClass,
+        index: usize,
+    ) -> Result<Board<'library>> {
         Board::new(class, index)
     }
 
-    pub fn get_board_from_name<'library>(&'library self, name: &str) -> Board<'library> {
+    /// Opens every board of `class` in turn, [`BoardClass::ALL`] covers all attached boards.
+    pub fn boards<'library>(
+        &'library self,
+        class: BoardClass,
+    ) -> impl Iterator<Item = Result<Board<'library>>> + 'library {
+        (0..Board::amount(class)).map(move |index| Board::new(class, index))
+    }
+
+    /// Opens the first board of `class`, e.g. the CPU module on systems with several boards.
+    pub fn get_board_from_class<'library>(
+        &'library self,
+        class: BoardClass,
+    ) -> Result<Board<'library>> {
+        Board::new(class, 0)
+    }
+
+    /// Opens the first board of the class configured with [`CongatecBuilder::board_class`].
+    pub fn get_default_board<'library>(&'library self) -> Result<Board<'library>> {
+        Board::new(self.board_class, 0)
+    }
+
+    pub fn get_board_from_name<'library>(&'library self, name: &str) -> Result<Board<'library>> {
         Board::from_name(name)
     }
 }
 
+/// A linked library always has all functions.
+#[cfg(not(all(feature = "dynamic", not(feature = "mock"))))]
+fn is_exported(_function: &str) -> bool {
+    true
+}
+
+fn install(install: bool) -> Result<()> {
+    // Not using ffi_call! here, it would report the missing driver as the failure reason.
+    let _lock = lock();
+    if unsafe { CgosLibInstall(install.into()) } == 0 {
+        return Err(CgosError::Failed {
+            function: "CgosLibInstall",
+            code: ErrorCode::last(),
+        });
+    }
+    Ok(())
+}
+
 impl Drop for Congatec {
     fn drop(&mut self) {
-        assert_ne!(unsafe { CgosLibUninitialize() }, 0);
+        if self.initialized && ffi_value!(CgosLibUninitialize()) == 0 {
+            #[cfg(feature = "tracing")]
+            tracing::warn!("uninitializing CGOS failed");
+        }
+    }
+}
+
+/// CGOS version word, split into major and minor in the high bytes and the build in the low half.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Version {
+    pub major: u8,
+    pub minor: u8,
+    pub build: u16,
+}
+
+impl From<u32> for Version {
+    fn from(version: u32) -> Self {
+        Self {
+            major: (version >> 24) as u8,
+            minor: (version >> 16) as u8,
+            build: version as u16,
+        }
+    }
+}
+
+impl Display for Version {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(formatter, "{}.{}.{}", self.major, self.minor, self.build)
+    }
+}
+
+/// Optional parts of the CGOS API, not every libcgos release implements all of them.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Feature {
+    DriverInstall,
+    BoardOptions,
+    BootErrorLog,
+    RunningTimeMeter,
+    Cgbc,
+    SensorLimits,
+    IoDirection,
+    IoNames,
+    StorageErase,
+    StorageLock,
+    WatchdogStages,
+    Performance,
+}
+
+impl Feature {
+    /// CGOS functions the feature needs.
+    pub fn functions(self) -> &'static [&'static str] {
+        match self {
+            Feature::DriverInstall => &["CgosLibInstall"],
+            Feature::BoardOptions => &["CgosBoardGetOption", "CgosBoardSetOption"],
+            Feature::BootErrorLog => &["CgosBoardGetBootErrorLog"],
+            Feature::RunningTimeMeter => &["CgosBoardGetRunningTimeMeter"],
+            Feature::Cgbc => &[
+                "CgosCgbcGetInfo",
+                "CgosCgbcHandleCommand",
+                "CgosCgbcReadWrite",
+                "CgosCgbcSetControl",
+            ],
+            Feature::SensorLimits => &[
+                "CgosTemperatureSetLimits",
+                "CgosFanSetLimits",
+                "CgosVoltageSetLimits",
+            ],
+            Feature::IoDirection => &[
+                "CgosIOGetDirectionCaps",
+                "CgosIOGetDirection",
+                "CgosIOSetDirection",
+            ],
+            Feature::IoNames => &["CgosIOGetNameA"],
+            Feature::StorageErase => &["CgosStorageAreaErase", "CgosStorageAreaEraseStatus"],
+            Feature::StorageLock => &[
+                "CgosStorageAreaLock",
+                "CgosStorageAreaUnlock",
+                "CgosStorageAreaIsLocked",
+            ],
+            Feature::WatchdogStages => &["CgosWDogGetConfigStruct", "CgosWDogSetConfigStruct"],
+            Feature::Performance => &[
+                "CgosPerformanceGetCurrent",
+                "CgosPerformanceSetCurrent",
+                "CgosPerformanceGetPolicyCaps",
+                "CgosPerformanceGetPolicy",
+                "CgosPerformanceSetPolicy",
+            ],
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct CongatecBuilder {
+    initialize: bool,
+    install_driver: bool,
+    retries: usize,
+    retry_delay: Duration,
+    board_class: BoardClass,
+    strict: bool,
+}
+
+impl CongatecBuilder {
+    /// Disable if another part of the process already initialized libcgos.
+    pub fn initialize(mut self, initialize: bool) -> Self {
+        self.initialize = initialize;
+        self
+    }
+
+    /// Installs the kernel driver if initializing fails, see [`Congatec::install_driver`].
+    pub fn install_driver(mut self, install_driver: bool) -> Self {
+        self.install_driver = install_driver;
+        self
+    }
+
+    /// Retries a failed open `retries` times, waiting `delay` in between, e.g. while the driver
+    /// is still loading during boot.
+    pub fn retries(mut self, retries: usize, delay: Duration) -> Self {
+        self.retries = retries;
+        self.retry_delay = delay;
+        self
+    }
+
+    /// Board class opened by [`Congatec::get_default_board`].
+    pub fn board_class(mut self, class: BoardClass) -> Self {
+        self.board_class = class;
+        self
+    }
+
+    /// Fails opening if no board of the board class is attached, instead of only once a board is
+    /// opened.
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    pub fn open(self) -> Result<Congatec> {
+        #[cfg(all(feature = "dynamic", not(feature = "mock")))]
+        crate::dynamic::load()?;
+        let mut attempt = 0;
+        loop {
+            match self.try_open() {
+                Err(_) if attempt < self.retries => {
+                    attempt += 1;
+                    sleep(self.retry_delay);
+                }
+                result => return result,
+            }
+        }
+    }
+
+    fn try_open(&self) -> Result<Congatec> {
+        if self.initialize {
+            if let Err(error) = ffi_call!(CgosLibInitialize()) {
+                if !self.install_driver {
+                    return Err(error);
+                }
+                install(true)?;
+                ffi_call!(CgosLibInitialize())?;
+            }
+        } else if ffi_value!(CgosLibIsAvailable()) == 0 {
+            return Err(CgosError::LibraryNotAvailable);
+        }
+        let library = Congatec {
+            initialized: self.initialize,
+            board_class: self.board_class,
+        };
+        if self.strict && Board::amount(self.board_class) == 0 {
+            return Err(CgosError::NoBoard {
+                class: self.board_class,
+            });
+        }
+        Ok(library)
+    }
+}
+
+impl Default for CongatecBuilder {
+    fn default() -> Self {
+        Self {
+            initialize: true,
+            install_driver: false,
+            retries: 0,
+            retry_delay: Duration::ZERO,
+            board_class: BoardClass::ALL,
+            strict: false,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+    use super::*;
+    use crate::mock::{install, test_lock, MockBoard};
+
+    #[test]
+    fn builder_opens_boards() {
+        let _lock = test_lock();
+        install(vec![MockBoard::default()]);
+        let library = Congatec::builder().open().unwrap();
+        assert_eq!(library.get_default_board().unwrap().name().unwrap(), "MOCK");
+    }
+
+    #[test]
+    fn uninitialized_library_is_not_available() {
+        let _lock = test_lock();
+        install(vec![MockBoard::default()]);
+        let result = Congatec::builder().initialize(false).open();
+        assert!(matches!(result, Err(CgosError::LibraryNotAvailable)));
+    }
+
+    #[test]
+    fn library_initialized_elsewhere_stays_initialized() {
+        let _lock = test_lock();
+        install(vec![MockBoard::default()]);
+        let owner = Congatec::new().unwrap();
+        drop(Congatec::builder().initialize(false).open().unwrap());
+        assert_ne!(ffi_value!(CgosLibIsAvailable()), 0);
+        drop(owner);
+        assert_eq!(ffi_value!(CgosLibIsAvailable()), 0);
+    }
+
+    #[test]
+    fn strict_builder_requires_a_board() {
+        let _lock = test_lock();
+        install(Vec::new());
+        assert!(Congatec::builder().open().is_ok());
+        let result = Congatec::builder().strict(true).open();
+        assert!(matches!(result, Err(CgosError::NoBoard { .. })));
+    }
+
+    #[test]
+    fn failed_uninitialize_does_not_panic() {
+        let _lock = test_lock();
+        install(Vec::new());
+        let library = Congatec::new().unwrap();
+        assert_ne!(ffi_value!(CgosLibUninitialize()), 0);
+        drop(library);
     }
 }
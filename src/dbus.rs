@@ -0,0 +1,175 @@
+//! Boards served over D-Bus as `org.cgos.Board1`, so other processes can read sensors and feed
+//! watchdogs without linking libcgos themselves.
+//!
+//! On the system bus only root may call the watchdog methods. The bus itself needs the policy in
+//! `dbus-1/system.d/org.cgos.conf` to let the daemon own [`BUS_NAME`], it also restricts those
+//! methods to root.
+
+use std::{sync::Arc, time::Duration};
+
+use zbus::{
+    blocking::{Connection, ConnectionBuilder},
+    dbus_interface, fdo, MessageHeader,
+};
+
+use crate::{
+    board::BoardClass,
+    congatec::Congatec,
+    error::{CgosError, Result},
+    owned::SharedBoard,
+};
+
+/// Well-known name requested by [`serve`].
+pub const BUS_NAME: &str = "org.cgos";
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Bus {
+    System,
+    Session,
+}
+
+/// Serves every board at `/org/cgos/Board<index>` under [`BUS_NAME`].
+///
+/// Requests are handled on the connection's own thread for as long as it is kept alive.
+pub fn serve(library: Arc<Congatec>, bus: Bus) -> Result<Connection> {
+    let mut builder = match bus {
+        Bus::System => ConnectionBuilder::system()?,
+        Bus::Session => ConnectionBuilder::session()?,
+    };
+    for index in 0..library.get_number_of_boards(BoardClass::ALL) {
+        let board = SharedBoard::open(library.clone(), BoardClass::ALL, index)?;
+        builder = builder.serve_at(
+            format!("/org/cgos/Board{index}"),
+            BoardInterface::new(board, bus),
+        )?;
+    }
+    Ok(builder.name(BUS_NAME)?.build()?)
+}
+
+pub struct BoardInterface {
+    board: Arc<SharedBoard>,
+    /// Whether only root may change watchdogs, the session bus is only reachable by its user.
+    restricted: bool,
+}
+
+impl BoardInterface {
+    pub fn new(board: Arc<SharedBoard>, bus: Bus) -> Self {
+        Self {
+            board,
+            restricted: bus == Bus::System,
+        }
+    }
+
+    async fn authorize(
+        &self,
+        header: &MessageHeader<'_>,
+        connection: &zbus::Connection,
+    ) -> fdo::Result<()> {
+        if !self.restricted {
+            return Ok(());
+        }
+        let sender = header
+            .sender()?
+            .ok_or_else(|| fdo::Error::AccessDenied("caller unknown".to_string()))?;
+        let user = fdo::DBusProxy::new(connection)
+            .await?
+            .get_connection_unix_user(sender.clone().into())
+            .await?;
+        if user != 0 {
+            return Err(fdo::Error::AccessDenied(
+                "only root may change watchdogs".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[dbus_interface(name = "org.cgos.Board1")]
+impl BoardInterface {
+    #[dbus_interface(property)]
+    fn name(&self) -> fdo::Result<String> {
+        Ok(self.board.name()?)
+    }
+
+    /// Type, degrees Celsius and status bits of every temperature sensor.
+    fn temperatures(&self) -> fdo::Result<Vec<(String, f64, u32)>> {
+        (0..self.board.get_number_of_temperatures())
+            .map(|index| {
+                let sensor = self.board.get_temperature(index)?;
+                let (celsius, status) = sensor.current()?;
+                Ok((sensor.info()?.type_.to_string(), celsius.0, status.bits()))
+            })
+            .collect()
+    }
+
+    /// Type, RPM and status bits of every fan.
+    fn fans(&self) -> fdo::Result<Vec<(String, i32, u32)>> {
+        (0..self.board.get_number_of_fans())
+            .map(|index| {
+                let sensor = self.board.get_fan(index)?;
+                let (rpm, status) = sensor.current()?;
+                Ok((sensor.info()?.type_.to_string(), rpm, status.bits()))
+            })
+            .collect()
+    }
+
+    /// Type, millivolts and status bits of every voltage sensor.
+    fn voltages(&self) -> fdo::Result<Vec<(String, u32, u32)>> {
+        (0..self.board.get_number_of_voltages())
+            .map(|index| {
+                let sensor = self.board.get_voltage(index)?;
+                let (millivolts, status) = sensor.current()?;
+                Ok((
+                    sensor.info()?.type_.to_string(),
+                    millivolts.0,
+                    status.bits(),
+                ))
+            })
+            .collect()
+    }
+
+    /// Arms the watchdog to reboot the board unless triggered within `timeout_ms`.
+    async fn watchdog_arm(
+        &self,
+        #[zbus(header)] header: MessageHeader<'_>,
+        #[zbus(connection)] connection: &zbus::Connection,
+        index: u32,
+        timeout_ms: u64,
+    ) -> fdo::Result<()> {
+        self.authorize(&header, connection).await?;
+        if timeout_ms > u32::MAX.into() {
+            return Err(fdo::Error::InvalidArgs(format!(
+                "timeout of {timeout_ms} ms exceeds the {} ms CGOS supports",
+                u32::MAX
+            )));
+        }
+        let watchdog = self.board.get_watchdog(index as usize)?;
+        Ok(watchdog.set_timeout(Duration::from_millis(timeout_ms))?)
+    }
+
+    async fn watchdog_trigger(
+        &self,
+        #[zbus(header)] header: MessageHeader<'_>,
+        #[zbus(connection)] connection: &zbus::Connection,
+        index: u32,
+    ) -> fdo::Result<()> {
+        self.authorize(&header, connection).await?;
+        Ok(self.board.get_watchdog(index as usize)?.trigger()?)
+    }
+
+    async fn watchdog_disable(
+        &self,
+        #[zbus(header)] header: MessageHeader<'_>,
+        #[zbus(connection)] connection: &zbus::Connection,
+        index: u32,
+    ) -> fdo::Result<()> {
+        self.authorize(&header, connection).await?;
+        Ok(self.board.get_watchdog(index as usize)?.disable()?)
+    }
+}
+
+impl From<CgosError> for fdo::Error {
+    fn from(error: CgosError) -> Self {
+        fdo::Error::Failed(error.to_string())
+    }
+}
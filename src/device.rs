@@ -0,0 +1,65 @@
+//! Enumeration of the devices of a board, see [`Board::devices`].
+
+#[cfg(feature = "io")]
+use crate::io::IoPort;
+#[cfg(feature = "storage")]
+use crate::storage_area::{StorageArea, StorageAreaType};
+#[cfg(feature = "wdog")]
+use crate::watchdog::Watchdog;
+use crate::{board::Board, error::Result};
+#[cfg(feature = "sensors")]
+use crate::{fan::Fan, temperature::Temperature, voltage::Voltage};
+
+/// Kind of device a board has a number of, opened by index.
+pub trait DeviceClass<'library>: Sized + sealed::Sealed {
+    fn amount(board: &Board<'library>) -> usize;
+
+    fn open(board: &'library Board<'library>, index: usize) -> Result<Self>;
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+#[cfg(any(feature = "sensors", feature = "io", feature = "wdog"))]
+macro_rules! device_class {
+    ($device:ident, $amount:ident, $open:ident) => {
+        impl<'library> sealed::Sealed for $device<'library> {}
+
+        impl<'library> DeviceClass<'library> for $device<'library> {
+            fn amount(board: &Board<'library>) -> usize {
+                board.$amount()
+            }
+
+            fn open(board: &'library Board<'library>, index: usize) -> Result<Self> {
+                board.$open(index)
+            }
+        }
+    };
+}
+
+#[cfg(feature = "sensors")]
+device_class!(Temperature, get_number_of_temperatures, get_temperature);
+#[cfg(feature = "sensors")]
+device_class!(Fan, get_number_of_fans, get_fan);
+#[cfg(feature = "sensors")]
+device_class!(Voltage, get_number_of_voltages, get_voltage);
+#[cfg(feature = "io")]
+device_class!(IoPort, get_number_of_io_ports, get_io_port);
+#[cfg(feature = "wdog")]
+device_class!(Watchdog, get_number_of_watchdogs, get_watchdog);
+
+#[cfg(feature = "storage")]
+impl<'library> sealed::Sealed for StorageArea<'library> {}
+
+/// Storage areas of all types, indexed like [`Board::get_storage_area_from_index`].
+#[cfg(feature = "storage")]
+impl<'library> DeviceClass<'library> for StorageArea<'library> {
+    fn amount(board: &Board<'library>) -> usize {
+        board.get_number_of_storage_areas(StorageAreaType::UNKNOWN)
+    }
+
+    fn open(board: &'library Board<'library>, index: usize) -> Result<Self> {
+        board.get_storage_area_from_index(index)
+    }
+}
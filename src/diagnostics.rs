@@ -0,0 +1,145 @@
+//! Report of everything CGOS knows about the system, see [`Congatec::diagnostics`].
+
+use std::time::Duration;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "io")]
+use crate::io::DirectionCaps;
+#[cfg(feature = "storage")]
+use crate::storage_area::StorageKind;
+#[cfg(feature = "wdog")]
+use crate::watchdog::WatchdogConfig;
+use crate::{
+    board::{Board, BoardClass, BoardInfo},
+    congatec::{Congatec, Version},
+};
+#[cfg(feature = "sensors")]
+use crate::{
+    fan::FanInfo, sensor::HealthSnapshot, temperature::TemperatureInfo, voltage::VoltageInfo,
+};
+
+/// Failed queries are `None` so one broken part does not hide the rest of the report.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug)]
+pub struct Diagnostics {
+    pub library_version: Version,
+    pub driver_version: Version,
+    /// All attached boards, `None` for boards that could not be opened.
+    pub boards: Vec<Option<BoardDiagnostics>>,
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug)]
+pub struct BoardDiagnostics {
+    pub name: Option<String>,
+    pub info: Option<BoardInfo>,
+    pub boot_count: Option<usize>,
+    pub running_time: Option<Duration>,
+    #[cfg(feature = "sensors")]
+    pub temperatures: Vec<Option<TemperatureInfo>>,
+    #[cfg(feature = "sensors")]
+    pub fans: Vec<Option<FanInfo>>,
+    #[cfg(feature = "sensors")]
+    pub voltages: Vec<Option<VoltageInfo>>,
+    #[cfg(feature = "sensors")]
+    pub health: HealthSnapshot,
+    #[cfg(feature = "io")]
+    pub io_ports: Vec<Option<IoPortDiagnostics>>,
+    #[cfg(feature = "storage")]
+    pub storage_areas: Vec<Option<StorageAreaDiagnostics>>,
+    #[cfg(feature = "wdog")]
+    pub watchdogs: Vec<Option<WatchdogConfig>>,
+}
+
+#[cfg(feature = "io")]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug)]
+pub struct IoPortDiagnostics {
+    pub name: Option<String>,
+    pub direction_caps: Option<DirectionCaps>,
+    /// Set bits are inputs.
+    pub direction: Option<u32>,
+    pub value: Option<u32>,
+}
+
+#[cfg(feature = "storage")]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug)]
+pub struct StorageAreaDiagnostics {
+    pub kind: StorageKind,
+    pub size: usize,
+    pub block_size: usize,
+    pub locked: bool,
+}
+
+impl Diagnostics {
+    pub(crate) fn collect(library: &Congatec) -> Self {
+        Self {
+            library_version: library.version(),
+            driver_version: library.driver_version(),
+            boards: library
+                .boards(BoardClass::ALL)
+                .map(|board| board.ok().map(|board| BoardDiagnostics::collect(&board)))
+                .collect(),
+        }
+    }
+}
+
+impl BoardDiagnostics {
+    pub(crate) fn collect<'library>(board: &'library Board<'library>) -> Self {
+        Self {
+            name: board.name().ok(),
+            info: board.info().ok(),
+            boot_count: board.boot_count().ok(),
+            running_time: board.running_time().ok(),
+            #[cfg(feature = "sensors")]
+            temperatures: board
+                .temperatures()
+                .map(|sensor| sensor.and_then(|sensor| sensor.info()).ok())
+                .collect(),
+            #[cfg(feature = "sensors")]
+            fans: board
+                .fans()
+                .map(|sensor| sensor.and_then(|sensor| sensor.info()).ok())
+                .collect(),
+            #[cfg(feature = "sensors")]
+            voltages: board
+                .voltages()
+                .map(|sensor| sensor.and_then(|sensor| sensor.info()).ok())
+                .collect(),
+            #[cfg(feature = "sensors")]
+            health: board.health_snapshot(),
+            #[cfg(feature = "io")]
+            io_ports: board
+                .io_ports()
+                .map(|port| {
+                    port.ok().map(|port| IoPortDiagnostics {
+                        name: port.name().ok(),
+                        direction_caps: port.direction_caps().ok(),
+                        direction: port.get_direction().ok(),
+                        value: port.read().ok(),
+                    })
+                })
+                .collect(),
+            #[cfg(feature = "storage")]
+            storage_areas: board
+                .storage_areas()
+                .map(|area| {
+                    area.ok().map(|area| StorageAreaDiagnostics {
+                        kind: area.kind(),
+                        size: area.size(),
+                        block_size: area.block_size(),
+                        locked: area.is_locked(),
+                    })
+                })
+                .collect(),
+            #[cfg(feature = "wdog")]
+            watchdogs: board
+                .watchdogs()
+                .map(|watchdog| watchdog.and_then(|watchdog| watchdog.config()).ok())
+                .collect(),
+        }
+    }
+}
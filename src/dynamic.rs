@@ -0,0 +1,104 @@
+//! Backend loading libcgos at runtime instead of linking it, enabled with the `dynamic` feature.
+//!
+//! The library is loaded when a [`Congatec`](crate::congatec::Congatec) is opened. Until it has
+//! been loaded, and for functions the loaded library lacks, calls fail like unavailable ones.
+
+use std::sync::{Mutex, PoisonError};
+
+use libloading::Library;
+
+use crate::error::Result;
+
+/// Tried in order, the DLL is named differently across Windows SDK releases.
+#[cfg(windows)]
+const LIBRARY_NAMES: &[&str] = &["Cgos.dll", "CgosDll.dll"];
+#[cfg(not(windows))]
+const LIBRARY_NAMES: &[&str] = &["libcgos.so"];
+
+/// Leaked once loaded, the library stays loaded until the process exits.
+static FUNCTIONS: Mutex<Option<&'static ffi::Functions>> = Mutex::new(None);
+
+fn functions() -> Option<&'static ffi::Functions> {
+    *FUNCTIONS.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Loads libcgos unless already loaded.
+pub(crate) fn load() -> Result<()> {
+    let mut functions = FUNCTIONS.lock().unwrap_or_else(PoisonError::into_inner);
+    if functions.is_some() {
+        return Ok(());
+    }
+    let mut last_error = None;
+    for name in LIBRARY_NAMES {
+        match unsafe { Library::new(name) } {
+            Ok(library) => {
+                let loaded = unsafe { ffi::Functions::load(library) };
+                *functions = Some(Box::leak(Box::new(loaded)));
+                return Ok(());
+            }
+            Err(error) => last_error = Some(error),
+        }
+    }
+    Err(last_error.expect("no library names to load").into())
+}
+
+/// Whether the loaded library exports `function`, false until it has been loaded.
+pub(crate) fn is_exported(function: &str) -> bool {
+    matches!(functions(), Some(functions) if functions.is_exported(function))
+}
+
+macro_rules! loaded {
+    ($(
+        fn $function:ident($($argument:ident: $type:ty),* $(,)?) -> $return:ty
+            $(=> [$($output:ident $(: $length:expr)?),*])?;
+    )*) => {
+        pub(crate) struct Functions {
+            // Keeps the function pointers valid.
+            _library: Library,
+            $($function: Option<unsafe extern "C" fn($($type),*) -> $return>,)*
+        }
+
+        impl Functions {
+            pub(crate) unsafe fn load(library: Library) -> Self {
+                Self {
+                    $($function: library
+                        .get(concat!(stringify!($function), "\0").as_bytes())
+                        .ok()
+                        .map(|symbol| *symbol),)*
+                    _library: library,
+                }
+            }
+
+            pub(crate) fn is_exported(&self, function: &str) -> bool {
+                match function {
+                    $(stringify!($function) => self.$function.is_some(),)*
+                    _ => false,
+                }
+            }
+        }
+
+        $(
+            pub unsafe fn $function($($argument: $type),*) -> $return {
+                match super::functions().and_then(|functions| functions.$function) {
+                    Some(function) => function($($argument),*),
+                    None => 0,
+                }
+            }
+        )*
+    };
+}
+
+pub(crate) mod ffi {
+    #![allow(dead_code)]
+    #![allow(non_snake_case)]
+    #![allow(clippy::missing_safety_doc)]
+
+    use std::ffi::{c_char, c_uchar, c_uint, c_void};
+
+    use libloading::Library;
+
+    use crate::bindings::cgos_functions;
+    pub use crate::bindings::libcgos::*;
+
+    cgos_functions!(loaded);
+}
@@ -0,0 +1,353 @@
+use std::sync::{Mutex, MutexGuard, PoisonError};
+#[cfg(feature = "wdog")]
+use std::time::Duration;
+
+use thiserror::Error;
+
+#[cfg(feature = "storage")]
+use crate::storage_area::StorageKind;
+use crate::{
+    bindings::{CgosLibGetLastError, CgosLibIsAvailable},
+    board::BoardClass,
+};
+
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum CgosError {
+    #[error("libcgos is not available or not initialized")]
+    LibraryNotAvailable,
+    #[cfg(all(feature = "dynamic", not(feature = "mock")))]
+    #[error("libcgos could not be loaded")]
+    LibraryNotLoaded(#[from] libloading::Error),
+    #[error("no board of class {class:?} attached")]
+    NoBoard { class: BoardClass },
+    #[error("index {index} is out of range, only {amount} available")]
+    IndexOutOfRange { index: usize, amount: usize },
+    #[cfg(feature = "io")]
+    #[error("pin {pin} is already claimed")]
+    PinAlreadyClaimed { pin: u32 },
+    #[cfg(feature = "io")]
+    #[error("pin {pin} cannot be used as {direction}")]
+    UnsupportedDirection { pin: u32, direction: &'static str },
+    #[cfg(feature = "io")]
+    #[error("{frequency} Hz is not a usable PWM frequency")]
+    InvalidFrequency { frequency: f32 },
+    #[cfg(feature = "io")]
+    #[error("{duty_cycle} is not a usable PWM duty cycle")]
+    InvalidDutyCycle { duty_cycle: f32 },
+    #[cfg(feature = "storage")]
+    #[error("{length} bytes at offset {offset} exceed the area size of {size} bytes")]
+    OutOfBounds {
+        offset: usize,
+        length: usize,
+        size: usize,
+    },
+    #[cfg(feature = "storage")]
+    #[error("no storage area of kind {kind:?}")]
+    StorageAreaNotFound { kind: StorageKind },
+    #[cfg(feature = "storage")]
+    #[error("data read back at offset {offset} differs from what was written")]
+    VerificationFailed { offset: usize },
+    #[cfg(feature = "storage")]
+    #[error("storage area erase failed")]
+    EraseFailed,
+    #[cfg(feature = "storage")]
+    #[error("operation timed out")]
+    Timeout,
+    #[cfg(feature = "storage")]
+    #[error("storage area does not contain the expected data format")]
+    InvalidFormat,
+    #[cfg(feature = "capture")]
+    #[error("line {line} of the capture is malformed")]
+    InvalidTrace { line: usize },
+    #[cfg(feature = "capture")]
+    #[error("{call} is not the next call of the replayed trace")]
+    ReplayDiverged { call: String },
+    #[cfg(feature = "storage")]
+    #[error("storage area is full")]
+    StorageFull,
+    #[cfg(feature = "storage")]
+    #[error("key or value too large to store")]
+    EntryTooLarge,
+    #[cfg(feature = "config-store")]
+    #[error("stored data has version {found}, expected {expected}")]
+    VersionMismatch { found: u32, expected: u32 },
+    #[cfg(feature = "config-store")]
+    #[error(transparent)]
+    Serialization(#[from] bincode::Error),
+    #[cfg(feature = "prometheus")]
+    #[error(transparent)]
+    Prometheus(#[from] prometheus::Error),
+    #[cfg(all(feature = "dbus", target_os = "linux"))]
+    #[error(transparent)]
+    DBus(#[from] zbus::Error),
+    #[cfg(feature = "mock-fixtures")]
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[cfg(feature = "mock-fixtures")]
+    #[error(transparent)]
+    Toml(#[from] toml::de::Error),
+    #[error("{length} bytes exceed the 32 bit lengths CGOS supports")]
+    TooLarge { length: usize },
+    #[error("{function} failed")]
+    Failed {
+        function: &'static str,
+        #[source]
+        code: ErrorCode,
+    },
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Nul(#[from] std::ffi::NulError),
+    #[cfg(all(feature = "systemd", target_os = "linux"))]
+    #[error("systemd watchdog is not enabled for this process")]
+    SystemdWatchdogNotEnabled,
+    #[cfg(feature = "wdog")]
+    #[error("timeout of {timeout:?} exceeds the 32 bit milliseconds CGOS supports")]
+    InvalidTimeout { timeout: Duration },
+    #[cfg(feature = "wdog")]
+    #[error("{amount} watchdog stages requested, at most {maximum} supported")]
+    TooManyStages { amount: usize, maximum: usize },
+    #[cfg(feature = "storage")]
+    #[error("expected {expected} bytes, got {actual}")]
+    SizeMismatch { expected: usize, actual: usize },
+}
+
+/// Error code reported by CgosLibGetLastError after a failed call.
+#[derive(Copy, Clone, Debug, Error, PartialEq, Eq, Hash)]
+#[error("CGOS error code {0:#x}")]
+pub struct ErrorCode(pub u32);
+
+impl ErrorCode {
+    pub(crate) fn last() -> Self {
+        Self(unsafe { CgosLibGetLastError() })
+    }
+}
+
+impl CgosError {
+    /// CGOS error code of a failed library call.
+    pub fn code(&self) -> Option<ErrorCode> {
+        match self {
+            CgosError::Failed { code, .. } => Some(*code),
+            _ => None,
+        }
+    }
+}
+
+pub type Result<T> = std::result::Result<T, CgosError>;
+
+/// Calls a CGOS function returning a success flag, turning a failure into a [`CgosError`].
+///
+/// Calls are serialized by [`lock()`], so the arguments must not call CGOS themselves. Fields
+/// given after a `;`, e.g. `unit = self.unit, offset`, are recorded in the tracing span of the
+/// call next to its function name and return value.
+macro_rules! ffi_call {
+    ($function:ident($($argument:expr),* $(,)?) $(; $($field:ident $(= $value:expr)?),* $(,)?)?) => {{
+        #[cfg(feature = "tracing")]
+        let _span = $crate::error::ffi_span!($function $(; $($field $(= $value)?),*)?);
+        let _lock = $crate::error::lock();
+        let return_value = unsafe { $function($($argument),*) };
+        #[cfg(feature = "tracing")]
+        _span.record("return_value", return_value);
+        $crate::error::check(stringify!($function), return_value)
+    }};
+}
+
+/// Calls a CGOS function returning a plain value, serialized and traced like [`ffi_call!`].
+macro_rules! ffi_value {
+    ($function:ident($($argument:expr),* $(,)?) $(; $($field:ident $(= $value:expr)?),* $(,)?)?) => {{
+        #[cfg(feature = "tracing")]
+        let _span = $crate::error::ffi_span!($function $(; $($field $(= $value)?),*)?);
+        let _lock = $crate::error::lock();
+        let return_value = unsafe { $function($($argument),*) };
+        #[cfg(feature = "tracing")]
+        {
+            _span.record("return_value", return_value);
+            tracing::trace!(return_value, "CGOS call returned");
+        }
+        return_value
+    }};
+}
+
+#[cfg(feature = "tracing")]
+macro_rules! ffi_span {
+    ($function:ident $(; $($field:ident $(= $value:expr)?),*)?) => {
+        tracing::trace_span!(
+            "cgos",
+            function = stringify!($function),
+            return_value = tracing::field::Empty
+            $($(, $field $(= $value)?)*)?
+        )
+        .entered()
+    };
+}
+
+pub(crate) use ffi_call;
+#[cfg(feature = "tracing")]
+pub(crate) use ffi_span;
+pub(crate) use ffi_value;
+
+static LOCK: Mutex<()> = Mutex::new(());
+
+/// libcgos is not guaranteed to be reentrant, every call into it holds this lock. The error code
+/// of a failed call is read while still holding it, so it cannot belong to another thread's call.
+pub(crate) fn lock() -> MutexGuard<'static, ()> {
+    LOCK.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+pub(crate) fn check(function: &'static str, return_value: u32) -> Result<()> {
+    if return_value != 0 {
+        #[cfg(feature = "tracing")]
+        tracing::trace!(function, "CGOS call succeeded");
+        return Ok(());
+    }
+    #[cfg(feature = "capture")]
+    if let Some(call) = crate::capture::diverged() {
+        return Err(CgosError::ReplayDiverged { call });
+    }
+    if unsafe { CgosLibIsAvailable() } == 0 {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(function, "CGOS call failed, library not available");
+        return Err(CgosError::LibraryNotAvailable);
+    }
+    let code = ErrorCode::last();
+    #[cfg(feature = "tracing")]
+    tracing::debug!(function, code = code.0, "CGOS call failed");
+    Err(CgosError::Failed { function, code })
+}
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+    use super::*;
+    use crate::{
+        bindings::{CgosBoardOpen, CgosLibInitialize, CgosLibUninitialize},
+        mock::{install, test_lock, MockBoard},
+    };
+
+    fn open_board() -> Result<()> {
+        let mut handle = 0;
+        ffi_call!(CgosBoardOpen(0, 0, 0, &mut handle))
+    }
+
+    #[test]
+    fn successful_call_is_ok() {
+        let _lock = test_lock();
+        install(vec![MockBoard::default()]);
+        ffi_call!(CgosLibInitialize()).unwrap();
+        open_board().unwrap();
+        ffi_call!(CgosLibUninitialize()).unwrap();
+    }
+
+    #[test]
+    fn failure_without_library_is_not_available() {
+        let _lock = test_lock();
+        install(Vec::new());
+        assert!(matches!(open_board(), Err(CgosError::LibraryNotAvailable)));
+    }
+
+    #[test]
+    fn failure_reports_function_and_code() {
+        let _lock = test_lock();
+        install(Vec::new());
+        ffi_call!(CgosLibInitialize()).unwrap();
+        let error = open_board().unwrap_err();
+        ffi_call!(CgosLibUninitialize()).unwrap();
+        assert!(matches!(
+            error,
+            CgosError::Failed {
+                function: "CgosBoardOpen",
+                code: ErrorCode(1),
+            }
+        ));
+        assert_eq!(error.code(), Some(ErrorCode(1)));
+    }
+}
+
+#[cfg(all(test, feature = "mock", feature = "tracing"))]
+mod tracing_tests {
+    use std::{
+        fmt::Debug,
+        sync::{Arc, Mutex},
+    };
+
+    use tracing::{
+        field::{Field, Visit},
+        span::{Attributes, Id, Record},
+        subscriber::with_default,
+        Event, Metadata, Subscriber,
+    };
+
+    use crate::{
+        board::BoardClass,
+        congatec::Congatec,
+        mock::{install, test_lock, MockBoard, MockStorageArea},
+        storage_area::StorageKind,
+    };
+
+    /// Collects the fields of all spans as `name=value` strings.
+    #[derive(Clone, Default)]
+    struct Fields(Arc<Mutex<Vec<String>>>);
+
+    impl Visit for Fields {
+        fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+            self.0
+                .lock()
+                .unwrap()
+                .push(format!("{}={value:?}", field.name()));
+        }
+    }
+
+    impl Subscriber for Fields {
+        fn enabled(&self, _metadata: &Metadata) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &Attributes) -> Id {
+            span.record(&mut self.clone());
+            Id::from_u64(1)
+        }
+
+        fn record(&self, _span: &Id, values: &Record) {
+            values.record(&mut self.clone());
+        }
+
+        fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+        fn event(&self, _event: &Event) {}
+
+        fn enter(&self, _span: &Id) {}
+
+        fn exit(&self, _span: &Id) {}
+    }
+
+    #[test]
+    fn calls_record_return_value_and_fields() {
+        let _lock = test_lock();
+        install(vec![MockBoard {
+            storage_areas: vec![MockStorageArea::new(StorageKind::Eeprom, 128)],
+            ..Default::default()
+        }]);
+        let library = Congatec::new().unwrap();
+        let board = library.get_board(BoardClass::ALL, 0).unwrap();
+        let area = board.get_storage_area_from_index(0).unwrap();
+        let fields = Fields::default();
+        with_default(fields.clone(), || {
+            area.size();
+            area.read(8, &mut [0; 4]).unwrap();
+        });
+        let fields = fields.0.lock().unwrap();
+        for field in [
+            "function=\"CgosStorageAreaSize\"",
+            "return_value=128",
+            "function=\"CgosStorageAreaRead\"",
+            "offset=8",
+            "length=4",
+            "return_value=1",
+        ] {
+            assert!(
+                fields.iter().any(|recorded| recorded == field),
+                "{field} missing"
+            );
+        }
+    }
+}
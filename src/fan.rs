@@ -1,15 +1,18 @@
 use std::{
+    fmt::{self, Display, Formatter},
     marker::PhantomData,
     mem::{size_of, zeroed},
 };
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 use crate::{
     bindings::{
         CgosFanCount, CgosFanGetCurrent, CgosFanGetInfo, CgosFanSetLimits, CGOSFANINFO,
-        CGOS_TEMP_BACKPLANE, CGOS_TEMP_BOARD, CGOS_TEMP_BOTDIMM_ENV, CGOS_TEMP_BOX,
-        CGOS_TEMP_CHIPSETS, CGOS_TEMP_CPU, CGOS_TEMP_ENV, CGOS_TEMP_OTHER, CGOS_TEMP_TOPDIMM_ENV,
-        CGOS_TEMP_VIDEO,
+        CGOS_FAN_BOX, CGOS_FAN_CHIPSET, CGOS_FAN_CPU, CGOS_FAN_ENV, CGOS_FAN_OTHER, CGOS_FAN_VIDEO,
     },
+    error::{ffi_call, ffi_value, CgosError, Result},
     status::Status,
 };
 
@@ -21,48 +24,120 @@ pub struct Fan<'library> {
 
 impl<'library> Fan<'library> {
     pub(crate) fn amount(handle: u32) -> usize {
-        unsafe { CgosFanCount(handle) as usize }
+        ffi_value!(CgosFanCount(handle)) as usize
     }
 
-    pub(crate) fn new(handle: u32, index: usize) -> Fan<'library> {
-        Self {
+    pub(crate) fn new(handle: u32, index: usize) -> Result<Fan<'library>> {
+        let amount = Self::amount(handle);
+        if index >= amount {
+            return Err(CgosError::IndexOutOfRange { index, amount });
+        }
+        Ok(Self {
             handle,
             index: index.try_into().unwrap(),
             _library_lifetime: PhantomData,
+        })
+    }
+
+    /// Board handle and sensor index to pass to CGOS functions directly.
+    pub fn raw_parts(&self) -> (u32, u32) {
+        (self.handle, self.index)
+    }
+
+    /// # Safety
+    ///
+    /// `handle` has to stay open for `'library` and `index` is not validated.
+    pub unsafe fn from_raw_parts(handle: u32, index: u32) -> Self {
+        Self {
+            handle,
+            index,
+            _library_lifetime: PhantomData,
+        }
+    }
+
+    /// Copy not tied to the library lifetime, for background threads whose owner is.
+    ///
+    /// The owner has to stop the thread when dropped, leaking it outlives the board handle.
+    pub(crate) fn detached(&self) -> Fan<'static> {
+        Fan {
+            handle: self.handle,
+            index: self.index,
+            _library_lifetime: PhantomData,
         }
     }
 
-    pub fn info(&self) -> FanInfo {
+    pub fn info(&self) -> Result<FanInfo> {
         let mut info: CGOSFANINFO = unsafe { zeroed() };
         info.dwSize = size_of::<CGOSFANINFO>() as u32;
-        assert_ne!(
-            unsafe { CgosFanGetInfo(self.handle, self.index, &mut info) },
-            0
-        );
-        info.into()
+        ffi_call!(CgosFanGetInfo(self.handle, self.index, &mut info))?;
+        Ok(info.into())
     }
 
-    pub fn current(&self) -> (i32, Status) {
+    pub fn current(&self) -> Result<(i32, Status)> {
         let mut value = 0;
         let mut flags = 0;
-        assert_ne!(
-            unsafe { CgosFanGetCurrent(self.handle, self.index, &mut value, &mut flags) },
-            0,
-        );
-        (value as i32, Status::from_bits_truncate(flags))
+        ffi_call!(CgosFanGetCurrent(
+            self.handle,
+            self.index,
+            &mut value,
+            &mut flags
+        ))?;
+        Ok((value as i32, Status::from_bits_truncate(flags)))
+    }
+
+    pub fn limits(&self) -> Result<(FanThreshold, FanThreshold)> {
+        let info = self.info()?;
+        Ok((
+            FanThreshold {
+                alarm: info.alarm_low,
+                hysteresis: info.hysteresis_low,
+            },
+            FanThreshold {
+                alarm: info.alarm_high,
+                hysteresis: info.hysteresis_high,
+            },
+        ))
     }
 
-    pub fn set_limits(&self, info: FanInfo) {
+    /// Keeps all other fields of the fan info as currently reported.
+    pub fn set_limits(&self, low: FanThreshold, high: FanThreshold) -> Result<()> {
+        let info = FanInfo {
+            alarm_low: low.alarm,
+            hysteresis_low: low.hysteresis,
+            alarm_high: high.alarm,
+            hysteresis_high: high.hysteresis,
+            ..self.info()?
+        };
+        self.set_info(info)
+    }
+
+    /// Sets the range the fan controller drives its output (e.g. PWM duty) in, where supported.
+    pub fn set_output_range(&self, minimum: i32, maximum: i32) -> Result<()> {
+        let info = FanInfo {
+            out_minimum: minimum,
+            out_maximum: maximum,
+            ..self.info()?
+        };
+        self.set_info(info)
+    }
+
+    pub fn set_info(&self, info: FanInfo) -> Result<()> {
         let mut info = info.into();
-        assert_ne!(
-            unsafe { CgosFanSetLimits(self.handle, self.index, &mut info) },
-            0,
-        );
+        ffi_call!(CgosFanSetLimits(self.handle, self.index, &mut info))
     }
 }
 
+/// Alarm speed in RPM and the hysteresis before the alarm clears again.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FanThreshold {
+    pub alarm: i32,
+    pub hysteresis: i32,
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Copy, Clone, Debug)]
 pub struct FanInfo {
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
     pub type_: FanType,
     pub status: Status,
     pub alarm: i32,
@@ -96,53 +171,59 @@ impl From<CGOSFANINFO> for FanInfo {
     }
 }
 
-impl Into<CGOSFANINFO> for FanInfo {
-    fn into(self) -> CGOSFANINFO {
+impl From<FanInfo> for CGOSFANINFO {
+    fn from(info: FanInfo) -> Self {
         CGOSFANINFO {
             dwSize: size_of::<CGOSFANINFO>() as u32,
-            dwType: self.type_.into(),
-            dwFlags: self.status.bits(),
-            dwAlarm: self.alarm as u32,
-            dwSpeedNom: self.speed_nominal as u32,
-            dwMin: self.minimum as u32,
-            dwMax: self.maximum as u32,
-            dwAlarmHi: self.alarm_high as u32,
-            dwHystHi: self.hysteresis_high as u32,
-            dwAlarmLo: self.alarm_low as u32,
-            dwHystLo: self.hysteresis_low as u32,
-            dwOutMin: self.out_minimum as u32,
-            dwOutMax: self.out_maximum as u32,
+            dwType: info.type_.into(),
+            dwFlags: info.status.bits(),
+            dwAlarm: info.alarm as u32,
+            dwSpeedNom: info.speed_nominal as u32,
+            dwMin: info.minimum as u32,
+            dwMax: info.maximum as u32,
+            dwAlarmHi: info.alarm_high as u32,
+            dwHystHi: info.hysteresis_high as u32,
+            dwAlarmLo: info.alarm_low as u32,
+            dwHystLo: info.hysteresis_low as u32,
+            dwOutMin: info.out_minimum as u32,
+            dwOutMax: info.out_maximum as u32,
         }
     }
 }
 
-#[derive(Copy, Clone, Debug)]
+impl Display for FanInfo {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "{} fan, nominal {} RPM, {} to {} RPM, {}",
+            self.type_, self.speed_nominal, self.minimum, self.maximum, self.status
+        )
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum FanType {
     Cpu,
     Box,
     Environment,
-    Board,
-    Backplane,
-    Chipsets,
+    Chipset,
     Video,
-    TopRAMEnvironment,
-    BottomRAMEnvironment,
     Other,
+    /// Type value not known to this crate.
+    Unknown(u32),
 }
 
-impl Into<u32> for FanType {
-    fn into(self) -> u32 {
-        match self {
-            FanType::Cpu => CGOS_TEMP_CPU,
-            FanType::Box => CGOS_TEMP_BOX,
-            FanType::Environment => CGOS_TEMP_ENV,
-            FanType::Board => CGOS_TEMP_BOARD,
-            FanType::Backplane => CGOS_TEMP_BACKPLANE,
-            FanType::Chipsets => CGOS_TEMP_CHIPSETS,
-            FanType::Video => CGOS_TEMP_VIDEO,
-            FanType::Other => CGOS_TEMP_OTHER,
-            FanType::TopRAMEnvironment => CGOS_TEMP_TOPDIMM_ENV,
-            FanType::BottomRAMEnvironment => CGOS_TEMP_BOTDIMM_ENV,
+impl From<FanType> for u32 {
+    fn from(type_: FanType) -> Self {
+        match type_ {
+            FanType::Cpu => CGOS_FAN_CPU,
+            FanType::Box => CGOS_FAN_BOX,
+            FanType::Environment => CGOS_FAN_ENV,
+            FanType::Chipset => CGOS_FAN_CHIPSET,
+            FanType::Video => CGOS_FAN_VIDEO,
+            FanType::Other => CGOS_FAN_OTHER,
+            FanType::Unknown(value) => value,
         }
     }
 }
@@ -150,17 +231,27 @@ impl Into<u32> for FanType {
 impl From<u32> for FanType {
     fn from(value: u32) -> Self {
         match value {
-            CGOS_TEMP_CPU => FanType::Cpu,
-            CGOS_TEMP_BOX => FanType::Box,
-            CGOS_TEMP_ENV => FanType::Environment,
-            CGOS_TEMP_BOARD => FanType::Board,
-            CGOS_TEMP_BACKPLANE => FanType::Backplane,
-            CGOS_TEMP_CHIPSETS => FanType::Chipsets,
-            CGOS_TEMP_VIDEO => FanType::Video,
-            CGOS_TEMP_OTHER => FanType::Other,
-            CGOS_TEMP_TOPDIMM_ENV => FanType::TopRAMEnvironment,
-            CGOS_TEMP_BOTDIMM_ENV => FanType::BottomRAMEnvironment,
-            _ => panic!("unexpected type {value}"),
+            CGOS_FAN_CPU => FanType::Cpu,
+            CGOS_FAN_BOX => FanType::Box,
+            CGOS_FAN_ENV => FanType::Environment,
+            CGOS_FAN_CHIPSET => FanType::Chipset,
+            CGOS_FAN_VIDEO => FanType::Video,
+            CGOS_FAN_OTHER => FanType::Other,
+            _ => FanType::Unknown(value),
+        }
+    }
+}
+
+impl Display for FanType {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            FanType::Cpu => write!(formatter, "CPU"),
+            FanType::Box => write!(formatter, "box"),
+            FanType::Environment => write!(formatter, "environment"),
+            FanType::Chipset => write!(formatter, "chipset"),
+            FanType::Video => write!(formatter, "video"),
+            FanType::Other => write!(formatter, "other"),
+            FanType::Unknown(value) => write!(formatter, "unknown type {value:#x}"),
         }
     }
 }
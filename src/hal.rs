@@ -0,0 +1,90 @@
+use std::time::Duration;
+
+use embedded_hal::{
+    digital::v2::{
+        InputPin as HalInputPin, OutputPin as HalOutputPin, StatefulOutputPin, ToggleableOutputPin,
+    },
+    watchdog::{Watchdog as Feed, WatchdogDisable, WatchdogEnable},
+};
+
+use crate::{
+    error::CgosError,
+    io::{self, InputPin, OutputPin},
+    watchdog::Watchdog,
+};
+
+/// # Panics
+///
+/// `feed()` panics if triggering the watchdog fails, the trait has no way to report errors.
+impl<'library> Feed for Watchdog<'library> {
+    fn feed(&mut self) {
+        self.trigger().expect("failed to trigger watchdog");
+    }
+}
+
+/// # Panics
+///
+/// `start()` panics if arming the watchdog fails, including with [`CgosError::InvalidTimeout`]
+/// for periods over `u32::MAX` milliseconds. The trait has no way to report errors.
+impl<'library> WatchdogEnable for Watchdog<'library> {
+    type Time = Duration;
+
+    fn start<T>(&mut self, period: T)
+    where
+        T: Into<Self::Time>,
+    {
+        self.set_timeout(period.into())
+            .expect("failed to arm watchdog");
+    }
+}
+
+/// # Panics
+///
+/// `disable()` panics if disabling the watchdog fails, the trait has no way to report errors.
+impl<'library> WatchdogDisable for Watchdog<'library> {
+    fn disable(&mut self) {
+        Watchdog::disable(self).expect("failed to disable watchdog");
+    }
+}
+
+impl<'port, 'library> HalInputPin for InputPin<'port, 'library> {
+    type Error = CgosError;
+
+    fn is_high(&self) -> Result<bool, Self::Error> {
+        io::InputPin::is_high(self)
+    }
+
+    fn is_low(&self) -> Result<bool, Self::Error> {
+        io::InputPin::is_low(self)
+    }
+}
+
+impl<'port, 'library> HalOutputPin for OutputPin<'port, 'library> {
+    type Error = CgosError;
+
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        io::OutputPin::set_low(self)
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        io::OutputPin::set_high(self)
+    }
+}
+
+impl<'port, 'library> StatefulOutputPin for OutputPin<'port, 'library> {
+    fn is_set_high(&self) -> Result<bool, Self::Error> {
+        io::OutputPin::is_set_high(self)
+    }
+
+    fn is_set_low(&self) -> Result<bool, Self::Error> {
+        Ok(!io::OutputPin::is_set_high(self)?)
+    }
+}
+
+impl<'port, 'library> ToggleableOutputPin for OutputPin<'port, 'library> {
+    type Error = CgosError;
+
+    fn toggle(&mut self) -> Result<(), Self::Error> {
+        io::OutputPin::toggle(self)
+    }
+}
@@ -0,0 +1,581 @@
+use std::{
+    collections::BTreeMap,
+    marker::PhantomData,
+    sync::{
+        mpsc::{channel, Receiver, RecvTimeoutError, Sender, TryIter},
+        Arc, Mutex, MutexGuard,
+    },
+    thread::{spawn, JoinHandle},
+    time::Duration,
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bindings::{
+        CgosIOCount, CgosIOGetDirection, CgosIOGetDirectionCaps, CgosIOGetNameA, CgosIOIsAvailable,
+        CgosIORead, CgosIOSetDirection, CgosIOWrite, CgosIOXorAndXor,
+    },
+    board::string_from_c_chars,
+    error::{ffi_call, ffi_value, CgosError, Result},
+};
+
+const PINS_PER_PORT: u32 = u32::BITS;
+/// Shortest PWM period, CGOS I/O calls are too slow to toggle pins any faster.
+const MINIMUM_PWM_PERIOD: Duration = Duration::from_millis(1);
+
+/// Pins claimed per board handle and port index, shared by all `IoPort`s of the process.
+static CLAIMED_PINS: Mutex<BTreeMap<(u32, u32), u32>> = Mutex::new(BTreeMap::new());
+
+fn claimed_pins() -> MutexGuard<'static, BTreeMap<(u32, u32), u32>> {
+    CLAIMED_PINS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+pub struct IoPort<'library> {
+    handle: u32,
+    index: u32,
+    _library_lifetime: PhantomData<&'library ()>,
+}
+
+impl<'library> IoPort<'library> {
+    pub(crate) fn amount(handle: u32) -> usize {
+        ffi_value!(CgosIOCount(handle)) as usize
+    }
+
+    pub(crate) fn new(handle: u32, index: usize) -> Result<IoPort<'library>> {
+        let amount = Self::amount(handle);
+        if index >= amount {
+            return Err(CgosError::IndexOutOfRange { index, amount });
+        }
+        Ok(Self {
+            handle,
+            index: index.try_into().unwrap(),
+            _library_lifetime: PhantomData,
+        })
+    }
+
+    /// Board handle and port index to pass to CGOS functions directly.
+    pub fn raw_parts(&self) -> (u32, u32) {
+        (self.handle, self.index)
+    }
+
+    /// # Safety
+    ///
+    /// `handle` has to stay open for `'library` and `index` is not validated.
+    pub unsafe fn from_raw_parts(handle: u32, index: u32) -> Self {
+        Self {
+            handle,
+            index,
+            _library_lifetime: PhantomData,
+        }
+    }
+
+    pub fn is_available(&self) -> bool {
+        ffi_value!(CgosIOIsAvailable(self.handle, self.index); unit = self.index) != 0
+    }
+
+    pub fn name(&self) -> Result<String> {
+        const SIZE: usize = 128;
+        let mut name = [0; SIZE];
+        ffi_call!(CgosIOGetNameA(
+            self.handle,
+            self.index,
+            name.as_mut_ptr(),
+            SIZE as u32,
+        ); unit = self.index)?;
+        Ok(string_from_c_chars(&name))
+    }
+
+    pub fn read(&self) -> Result<u32> {
+        let mut value = 0;
+        ffi_call!(CgosIORead(self.handle, self.index, &mut value); unit = self.index)?;
+        Ok(value)
+    }
+
+    pub fn write(&self, value: u32) -> Result<()> {
+        ffi_call!(CgosIOWrite(self.handle, self.index, value); unit = self.index, value)
+    }
+
+    /// Atomically replaces the port value with `((value ^ xor1) & and) ^ xor2` in the firmware.
+    pub fn modify(&self, xor1: u32, and: u32, xor2: u32) -> Result<()> {
+        ffi_call!(CgosIOXorAndXor(self.handle, self.index, xor1, and, xor2); unit = self.index, xor1, and, xor2)
+    }
+
+    pub fn set_bits(&self, mask: u32) -> Result<()> {
+        self.modify(0, !mask, mask)
+    }
+
+    pub fn clear_bits(&self, mask: u32) -> Result<()> {
+        self.modify(0, !mask, 0)
+    }
+
+    pub fn toggle_bits(&self, mask: u32) -> Result<()> {
+        self.modify(mask, !0, 0)
+    }
+
+    /// Returns the mask of pins currently configured as inputs, all other pins are outputs.
+    pub fn get_direction(&self) -> Result<u32> {
+        let mut inputs = 0;
+        ffi_call!(CgosIOGetDirection(self.handle, self.index, &mut inputs); unit = self.index)?;
+        Ok(inputs)
+    }
+
+    /// Configures the pins set in `inputs` as inputs and all other pins as outputs.
+    pub fn set_direction(&self, inputs: u32) -> Result<()> {
+        ffi_call!(CgosIOSetDirection(self.handle, self.index, inputs); unit = self.index, inputs)
+    }
+
+    pub fn direction_caps(&self) -> Result<DirectionCaps> {
+        let mut inputs = 0;
+        let mut outputs = 0;
+        ffi_call!(CgosIOGetDirectionCaps(
+            self.handle,
+            self.index,
+            &mut inputs,
+            &mut outputs
+        ); unit = self.index)?;
+        Ok(DirectionCaps { inputs, outputs })
+    }
+
+    /// Samples the port every `interval` and reports level changes of the pins in `mask`.
+    ///
+    /// The first sample only establishes the initial levels, failed samples are skipped. Polling
+    /// stops when the returned watcher is dropped.
+    pub fn watch(&self, interval: Duration, mask: u32) -> IoWatcher<'library> {
+        let (handle, index) = (self.handle, self.index);
+        let (stop, stopped) = channel::<()>();
+        let (sender, events) = channel();
+        let thread = spawn(move || {
+            let port = IoPort {
+                handle,
+                index,
+                _library_lifetime: PhantomData,
+            };
+            let mut previous = None;
+            while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+                let Ok(value) = port.read() else {
+                    continue;
+                };
+                if let Some(previous) = previous {
+                    let changed = (previous ^ value) & mask;
+                    for pin in (0..PINS_PER_PORT).filter(|pin| changed & (1 << pin) != 0) {
+                        let edge = if value & (1 << pin) != 0 {
+                            Edge::Rising
+                        } else {
+                            Edge::Falling
+                        };
+                        let _ = sender.send(IoEvent { pin, edge });
+                    }
+                }
+                previous = Some(value);
+            }
+        });
+        IoWatcher {
+            events,
+            stop: Some(stop),
+            thread: Some(thread),
+            _library_lifetime: PhantomData,
+        }
+    }
+
+    /// Takes ownership of a single pin until the returned `Pin` is dropped.
+    pub fn claim_pin(&self, pin: u32) -> Result<Pin<'_, 'library>> {
+        if pin >= PINS_PER_PORT {
+            return Err(CgosError::IndexOutOfRange {
+                index: pin as usize,
+                amount: PINS_PER_PORT as usize,
+            });
+        }
+        let mask = 1 << pin;
+        let mut claimed_pins = claimed_pins();
+        let claimed = claimed_pins.entry((self.handle, self.index)).or_default();
+        if *claimed & mask != 0 {
+            return Err(CgosError::PinAlreadyClaimed { pin });
+        }
+        *claimed |= mask;
+        Ok(Pin {
+            port: self,
+            pin,
+            mask,
+        })
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DirectionCaps {
+    pub inputs: u32,
+    pub outputs: u32,
+}
+
+impl DirectionCaps {
+    /// Pins that can be switched between input and output.
+    pub fn configurable(&self) -> u32 {
+        self.inputs & self.outputs
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Edge {
+    Rising,
+    Falling,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct IoEvent {
+    pub pin: u32,
+    pub edge: Edge,
+}
+
+/// Stops watching when dropped.
+///
+/// The polling thread only stops then, so a leaked watcher (e.g. `mem::forget`) keeps reading a
+/// port whose board may already be closed.
+pub struct IoWatcher<'library> {
+    events: Receiver<IoEvent>,
+    stop: Option<Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+    _library_lifetime: PhantomData<&'library ()>,
+}
+
+impl<'library> IoWatcher<'library> {
+    /// Returns the events that arrived since the last call without blocking.
+    pub fn try_iter(&self) -> TryIter<'_, IoEvent> {
+        self.events.try_iter()
+    }
+
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<IoEvent> {
+        self.events.recv_timeout(timeout).ok()
+    }
+}
+
+impl<'library> Iterator for IoWatcher<'library> {
+    type Item = IoEvent;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.events.recv().ok()
+    }
+}
+
+impl<'library> Drop for IoWatcher<'library> {
+    fn drop(&mut self) {
+        drop(self.stop.take());
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+pub struct Pin<'port, 'library> {
+    port: &'port IoPort<'library>,
+    pin: u32,
+    mask: u32,
+}
+
+impl<'port, 'library> Pin<'port, 'library> {
+    pub fn pin(&self) -> u32 {
+        self.pin
+    }
+
+    pub fn into_input(self) -> Result<InputPin<'port, 'library>> {
+        self.configure(true)?;
+        Ok(InputPin { pin: self })
+    }
+
+    pub fn into_output(self) -> Result<OutputPin<'port, 'library>> {
+        self.configure(false)?;
+        Ok(OutputPin { pin: self })
+    }
+
+    fn configure(&self, input: bool) -> Result<()> {
+        let caps = self.port.direction_caps()?;
+        let (capable, direction) = if input {
+            (caps.inputs, "input")
+        } else {
+            (caps.outputs, "output")
+        };
+        if capable & self.mask == 0 {
+            return Err(CgosError::UnsupportedDirection {
+                pin: self.pin,
+                direction,
+            });
+        }
+        // holding the lock keeps other pins of the process from interleaving their read-modify-write
+        let _claimed_pins = claimed_pins();
+        let inputs = self.port.get_direction()?;
+        if input {
+            self.port.set_direction(inputs | self.mask)
+        } else {
+            self.port.set_direction(inputs & !self.mask)
+        }
+    }
+
+    fn is_high(&self) -> Result<bool> {
+        Ok(self.port.read()? & self.mask != 0)
+    }
+}
+
+impl<'port, 'library> Drop for Pin<'port, 'library> {
+    fn drop(&mut self) {
+        if let Some(claimed) = claimed_pins().get_mut(&(self.port.handle, self.port.index)) {
+            *claimed &= !self.mask;
+        }
+    }
+}
+
+pub struct InputPin<'port, 'library> {
+    pin: Pin<'port, 'library>,
+}
+
+impl<'port, 'library> InputPin<'port, 'library> {
+    pub fn pin(&self) -> u32 {
+        self.pin.pin
+    }
+
+    pub fn is_high(&self) -> Result<bool> {
+        self.pin.is_high()
+    }
+
+    pub fn is_low(&self) -> Result<bool> {
+        Ok(!self.pin.is_high()?)
+    }
+}
+
+pub struct OutputPin<'port, 'library> {
+    pin: Pin<'port, 'library>,
+}
+
+impl<'port, 'library> OutputPin<'port, 'library> {
+    pub fn pin(&self) -> u32 {
+        self.pin.pin
+    }
+
+    pub fn set_high(&self) -> Result<()> {
+        self.pin.port.set_bits(self.pin.mask)
+    }
+
+    pub fn set_low(&self) -> Result<()> {
+        self.pin.port.clear_bits(self.pin.mask)
+    }
+
+    pub fn toggle(&self) -> Result<()> {
+        self.pin.port.toggle_bits(self.pin.mask)
+    }
+
+    /// Reads back the level currently driven on the pin.
+    pub fn is_set_high(&self) -> Result<bool> {
+        self.pin.is_high()
+    }
+}
+
+/// Drives an output pin with a square wave from a background thread.
+///
+/// The thread stops on drop or `into_pin()`. Leaking the PWM (e.g. with `mem::forget`) leaves it
+/// toggling the pin even after the board was closed.
+pub struct SoftPwm<'port, 'library> {
+    pin: Option<OutputPin<'port, 'library>>,
+    settings: Arc<Mutex<PwmSettings>>,
+    stop: Option<Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct PwmSettings {
+    period: Duration,
+    duty_cycle: f32,
+}
+
+impl<'port, 'library> SoftPwm<'port, 'library> {
+    /// Starts toggling `pin` at `frequency` Hz, high for `duty_cycle` (0.0 to 1.0) of each period.
+    ///
+    /// Frequencies above 1 kHz are rejected, duty cycles outside 0.0 to 1.0 are clamped.
+    pub fn new(pin: OutputPin<'port, 'library>, frequency: f32, duty_cycle: f32) -> Result<Self> {
+        let settings = Arc::new(Mutex::new(PwmSettings {
+            period: period_from_frequency(frequency)?,
+            duty_cycle: clamp_duty_cycle(duty_cycle)?,
+        }));
+        let (handle, index, mask) = (pin.pin.port.handle, pin.pin.port.index, pin.pin.mask);
+        let (stop, stopped) = channel::<()>();
+        let thread = spawn({
+            let settings = settings.clone();
+            move || {
+                let port = IoPort {
+                    handle,
+                    index,
+                    _library_lifetime: PhantomData,
+                };
+                let wait = |duration| {
+                    matches!(
+                        stopped.recv_timeout(duration),
+                        Err(RecvTimeoutError::Timeout)
+                    )
+                };
+                loop {
+                    let settings = *settings.lock().unwrap();
+                    let high = settings.period.mul_f32(settings.duty_cycle);
+                    let low = settings.period - high;
+                    if !high.is_zero() {
+                        let _ = port.set_bits(mask);
+                    }
+                    if !wait(high) {
+                        break;
+                    }
+                    if !low.is_zero() {
+                        let _ = port.clear_bits(mask);
+                    }
+                    if !wait(low) {
+                        break;
+                    }
+                }
+            }
+        });
+        Ok(Self {
+            pin: Some(pin),
+            settings,
+            stop: Some(stop),
+            thread: Some(thread),
+        })
+    }
+
+    pub fn set_frequency(&self, frequency: f32) -> Result<()> {
+        self.settings.lock().unwrap().period = period_from_frequency(frequency)?;
+        Ok(())
+    }
+
+    pub fn set_duty_cycle(&self, duty_cycle: f32) -> Result<()> {
+        self.settings.lock().unwrap().duty_cycle = clamp_duty_cycle(duty_cycle)?;
+        Ok(())
+    }
+
+    /// Stops the waveform and returns the pin, leaving it at whatever level it was last driven to.
+    pub fn into_pin(mut self) -> OutputPin<'port, 'library> {
+        self.stop();
+        self.pin.take().unwrap()
+    }
+
+    fn stop(&mut self) {
+        drop(self.stop.take());
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl<'port, 'library> Drop for SoftPwm<'port, 'library> {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+fn period_from_frequency(frequency: f32) -> Result<Duration> {
+    // Rejects NaN as well, and frequencies so high the thread would busy-loop.
+    if !(frequency > 0.0 && frequency.is_finite()) {
+        return Err(CgosError::InvalidFrequency { frequency });
+    }
+    Duration::try_from_secs_f32(1.0 / frequency)
+        .ok()
+        .filter(|&period| period >= MINIMUM_PWM_PERIOD)
+        .ok_or(CgosError::InvalidFrequency { frequency })
+}
+
+fn clamp_duty_cycle(duty_cycle: f32) -> Result<f32> {
+    // `clamp()` passes NaN through, which would panic the thread in `Duration::mul_f32()`.
+    if !duty_cycle.is_finite() {
+        return Err(CgosError::InvalidDutyCycle { duty_cycle });
+    }
+    Ok(duty_cycle.clamp(0.0, 1.0))
+}
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+    use std::thread::sleep;
+
+    use super::*;
+    use crate::{
+        board::BoardClass,
+        congatec::Congatec,
+        mock::{install, test_lock, with_board, MockBoard, MockIoPort},
+    };
+
+    fn install_port() {
+        install(vec![MockBoard {
+            io_ports: vec![MockIoPort {
+                name: "GPIO".to_string(),
+                inputs: 0x0f,
+                outputs: 0xff,
+                direction: 0x0f,
+                ..Default::default()
+            }],
+            ..Default::default()
+        }]);
+    }
+
+    fn port_value() -> u32 {
+        with_board(0, |board| board.io_ports[0].value)
+    }
+
+    #[test]
+    fn pins_are_claimed_once_until_dropped() {
+        let _lock = test_lock();
+        install_port();
+        let library = Congatec::new().unwrap();
+        let board = library.get_board(BoardClass::ALL, 0).unwrap();
+        let port = board.get_io_port(0).unwrap();
+        assert_eq!(port.name().unwrap(), "GPIO");
+        let pin = port.claim_pin(3).unwrap();
+        assert!(matches!(
+            port.claim_pin(3),
+            Err(CgosError::PinAlreadyClaimed { pin: 3 })
+        ));
+        let other = port.claim_pin(4).unwrap();
+        drop(pin);
+        port.claim_pin(3).unwrap();
+        assert!(matches!(
+            port.claim_pin(PINS_PER_PORT),
+            Err(CgosError::IndexOutOfRange { index: 32, .. })
+        ));
+        assert!(matches!(
+            other.into_input(),
+            Err(CgosError::UnsupportedDirection {
+                pin: 4,
+                direction: "input",
+            })
+        ));
+        let output = port.claim_pin(2).unwrap().into_output().unwrap();
+        assert_eq!(with_board(0, |board| board.io_ports[0].direction), 0x0b);
+        output.set_high().unwrap();
+        assert_eq!(port_value(), 0x04);
+    }
+
+    #[test]
+    fn pwm_follows_duty_cycle() {
+        let _lock = test_lock();
+        install_port();
+        let library = Congatec::new().unwrap();
+        let board = library.get_board(BoardClass::ALL, 0).unwrap();
+        let port = board.get_io_port(0).unwrap();
+        let pin = port.claim_pin(5).unwrap().into_output().unwrap();
+        let pwm = SoftPwm::new(pin, 100.0, 1.0).unwrap();
+        sleep(Duration::from_millis(50));
+        assert_eq!(port_value(), 0x20);
+        pwm.set_duty_cycle(0.0).unwrap();
+        sleep(Duration::from_millis(50));
+        assert_eq!(port_value(), 0x00);
+        assert!(matches!(
+            pwm.set_duty_cycle(f32::NAN),
+            Err(CgosError::InvalidDutyCycle { .. })
+        ));
+        assert!(matches!(
+            pwm.set_frequency(1_000_000.0),
+            Err(CgosError::InvalidFrequency { .. })
+        ));
+        let pin = pwm.into_pin();
+        assert!(matches!(
+            SoftPwm::new(pin, 100.0, f32::INFINITY),
+            Err(CgosError::InvalidDutyCycle { .. })
+        ));
+    }
+}
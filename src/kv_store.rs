@@ -0,0 +1,312 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    crc::crc32,
+    error::{CgosError, Result},
+    storage_area::StorageArea,
+};
+
+const MAGIC: &[u8; 4] = b"CGKV";
+const HEADER_LENGTH: usize = MAGIC.len() + 2 * 4;
+const END: u8 = 0x00;
+const ERASED: u8 = 0xff;
+const MAXIMUM_KEY_LENGTH: usize = 254;
+const TOMBSTONE: u16 = u16::MAX;
+const MAXIMUM_VALUE_LENGTH: usize = TOMBSTONE as usize - 1;
+
+/// Persistent key-value store kept as an append-only log in a storage area.
+///
+/// The log lives in one half of the area and every change appends one record to it. Once that
+/// half is full, the live entries are compacted into the other half, whose header with the next
+/// generation is written last, so power lost mid-compaction leaves the previous log in use. A
+/// record torn by power loss fails its checksum and is ignored together with everything after it.
+/// The area must accept byte-wise rewrites (EEPROM, CMOS or RAM, not unerased flash).
+pub struct KvStore<'area, 'library> {
+    area: &'area StorageArea<'library>,
+    entries: BTreeMap<String, Vec<u8>>,
+    half: usize,
+    generation: u32,
+    /// Offset of the end of the log within its half.
+    end: usize,
+}
+
+impl<'area, 'library> KvStore<'area, 'library> {
+    /// Opens the store previously created in `area` by `format()`.
+    pub fn open(area: &'area StorageArea<'library>) -> Result<Self> {
+        let data = area.read_all()?;
+        let (half, generation) = newest_half(&data).ok_or(CgosError::InvalidFormat)?;
+        let log = &data[half * half_size(area)..(half + 1) * half_size(area)];
+        let mut entries = BTreeMap::new();
+        let mut end = HEADER_LENGTH;
+        while let Some((key, value, length)) = parse_record(&log[end..]) {
+            match value {
+                Some(value) => entries.insert(key, value.to_vec()),
+                None => entries.remove(&key),
+            };
+            end += length;
+        }
+        Ok(Self {
+            area,
+            entries,
+            half,
+            generation,
+            end,
+        })
+    }
+
+    /// Creates an empty store in `area`, discarding whatever it contained.
+    pub fn format(area: &'area StorageArea<'library>) -> Result<Self> {
+        let entries = BTreeMap::new();
+        // Invalidates the second half first, an older log there must not outrank the new one.
+        area.write_chunked(half_size(area), &[ERASED; HEADER_LENGTH])?;
+        let end = rewrite(area, 0, 0, &entries)?;
+        Ok(Self {
+            area,
+            entries,
+            half: 0,
+            generation: 0,
+            end,
+        })
+    }
+
+    pub fn get(&self, key: &str) -> Option<&[u8]> {
+        self.entries.get(key).map(Vec::as_slice)
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.entries.contains_key(key)
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.entries.keys().map(String::as_str)
+    }
+
+    pub fn set(&mut self, key: &str, value: &[u8]) -> Result<()> {
+        if key.is_empty() || key.len() > MAXIMUM_KEY_LENGTH || value.len() > MAXIMUM_VALUE_LENGTH {
+            return Err(CgosError::EntryTooLarge);
+        }
+        if self.get(key) == Some(value) {
+            return Ok(());
+        }
+        self.append(key, Some(value))?;
+        self.entries.insert(key.to_string(), value.to_vec());
+        Ok(())
+    }
+
+    /// Removes `key`, returning whether it was present.
+    pub fn remove(&mut self, key: &str) -> Result<bool> {
+        if !self.contains_key(key) {
+            return Ok(false);
+        }
+        self.append(key, None)?;
+        self.entries.remove(key);
+        Ok(true)
+    }
+
+    /// Rewrites only the live entries into the other half, reclaiming the space of old records.
+    pub fn compact(&mut self) -> Result<()> {
+        let entries = self.entries.clone();
+        self.compact_to(&entries)
+    }
+
+    fn compact_to(&mut self, entries: &BTreeMap<String, Vec<u8>>) -> Result<()> {
+        let (half, generation) = (1 - self.half, self.generation.wrapping_add(1));
+        self.end = rewrite(self.area, half, generation, entries)?;
+        self.half = half;
+        self.generation = generation;
+        Ok(())
+    }
+
+    fn append(&mut self, key: &str, value: Option<&[u8]>) -> Result<()> {
+        let mut record = encode_record(key, value);
+        let length = record.len();
+        record.push(END);
+        if self.end + record.len() <= half_size(self.area) {
+            self.area
+                .write_chunked(self.half * half_size(self.area) + self.end, &record)?;
+            self.end += length;
+            return Ok(());
+        }
+        let mut entries = self.entries.clone();
+        match value {
+            Some(value) => entries.insert(key.to_string(), value.to_vec()),
+            None => entries.remove(key),
+        };
+        self.compact_to(&entries)
+    }
+}
+
+fn half_size(area: &StorageArea) -> usize {
+    area.size() / 2
+}
+
+/// Writes a compacted log of `entries` to `half` of `area` and returns where it ends.
+///
+/// The header goes last, until then the log in the other half stays the newest one.
+fn rewrite(
+    area: &StorageArea,
+    half: usize,
+    generation: u32,
+    entries: &BTreeMap<String, Vec<u8>>,
+) -> Result<usize> {
+    let mut records = Vec::new();
+    for (key, value) in entries {
+        records.extend(encode_record(key, Some(value)));
+    }
+    let end = HEADER_LENGTH + records.len();
+    records.push(END);
+    if HEADER_LENGTH + records.len() > half_size(area) {
+        return Err(CgosError::StorageFull);
+    }
+    let start = half * half_size(area);
+    area.write_chunked(start + HEADER_LENGTH, &records)?;
+    area.write_chunked(start, &encode_header(generation))?;
+    Ok(end)
+}
+
+/// Encodes a log header as magic, generation (little endian) and CRC-32.
+fn encode_header(generation: u32) -> Vec<u8> {
+    let mut header = MAGIC.to_vec();
+    header.extend(generation.to_le_bytes());
+    let checksum = crc32(&header);
+    header.extend(checksum.to_le_bytes());
+    header
+}
+
+/// Decodes the generation of the log starting at `data`, `None` if its header is invalid.
+fn parse_header(data: &[u8]) -> Option<u32> {
+    let header = data.get(..HEADER_LENGTH)?;
+    let (body, checksum) = header.split_at(HEADER_LENGTH - 4);
+    if !body.starts_with(MAGIC) || crc32(body) != u32::from_le_bytes(checksum.try_into().unwrap()) {
+        return None;
+    }
+    Some(u32::from_le_bytes(body[MAGIC.len()..].try_into().unwrap()))
+}
+
+/// Returns the half of `data` holding the log with the newest generation and that generation.
+fn newest_half(data: &[u8]) -> Option<(usize, u32)> {
+    let (first, second) = data.split_at(data.len() / 2);
+    match (parse_header(first), parse_header(second)) {
+        (Some(first), Some(second)) if (second.wrapping_sub(first) as i32) > 0 => Some((1, second)),
+        (Some(first), _) => Some((0, first)),
+        (None, Some(second)) => Some((1, second)),
+        (None, None) => None,
+    }
+}
+
+/// Encodes a record as key length, value length (little endian), key, value and CRC-32.
+///
+/// Removals are recorded with the tombstone value length and no value.
+fn encode_record(key: &str, value: Option<&[u8]>) -> Vec<u8> {
+    let value_length = value.map_or(TOMBSTONE, |value| value.len() as u16);
+    let mut record = vec![key.len() as u8];
+    record.extend(value_length.to_le_bytes());
+    record.extend(key.as_bytes());
+    record.extend(value.unwrap_or_default());
+    let checksum = crc32(&record);
+    record.extend(checksum.to_le_bytes());
+    record
+}
+
+/// Decodes the record at the start of `data`, `None` at the end of the log or on corruption.
+fn parse_record(data: &[u8]) -> Option<(String, Option<&[u8]>, usize)> {
+    let key_length = *data.first()? as usize;
+    if key_length == END as usize || key_length == ERASED as usize {
+        return None;
+    }
+    let value_length = u16::from_le_bytes(data.get(1..3)?.try_into().unwrap());
+    let stored_length = match value_length {
+        TOMBSTONE => 0,
+        value_length => value_length as usize,
+    };
+    let body_length = 3 + key_length + stored_length;
+    let record = data.get(..body_length + 4)?;
+    let (body, checksum) = record.split_at(body_length);
+    if crc32(body) != u32::from_le_bytes(checksum.try_into().unwrap()) {
+        return None;
+    }
+    let key = String::from_utf8(body[3..3 + key_length].to_vec()).ok()?;
+    let value = (value_length != TOMBSTONE).then(|| &body[3 + key_length..]);
+    Some((key, value, record.len()))
+}
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+    use super::*;
+    use crate::{
+        board::BoardClass,
+        congatec::Congatec,
+        mock::{install, test_lock, with_board, MockBoard, MockStorageArea},
+        storage_area::StorageKind,
+    };
+
+    fn install_area() {
+        install_blocked_area(0);
+    }
+
+    fn install_blocked_area(block_size: usize) {
+        let mut area = MockStorageArea::new(StorageKind::Eeprom, 128);
+        area.block_size = block_size;
+        install(vec![MockBoard {
+            storage_areas: vec![area],
+            ..Default::default()
+        }]);
+    }
+
+    #[test]
+    fn entries_survive_repeated_compaction() {
+        let _lock = test_lock();
+        install_area();
+        let library = Congatec::new().unwrap();
+        let board = library.get_board(BoardClass::ALL, 0).unwrap();
+        let area = board.get_storage_area_from_index(0).unwrap();
+        let mut store = KvStore::format(&area).unwrap();
+        store.set("fixed", b"value").unwrap();
+        // Each counter record takes 15 bytes, a 64 byte half overflows every few changes.
+        for counter in 0..20u8 {
+            store.set("counter", &[counter]).unwrap();
+        }
+        let store = KvStore::open(&area).unwrap();
+        assert_eq!(store.get("fixed"), Some(&b"value"[..]));
+        assert_eq!(store.get("counter"), Some(&[19][..]));
+    }
+
+    #[test]
+    fn compaction_torn_before_its_header_keeps_previous_log() {
+        let _lock = test_lock();
+        install_area();
+        let library = Congatec::new().unwrap();
+        let board = library.get_board(BoardClass::ALL, 0).unwrap();
+        let area = board.get_storage_area_from_index(0).unwrap();
+        let mut store = KvStore::format(&area).unwrap();
+        store.set("first", &[1]).unwrap();
+        store.set("second", &[2]).unwrap();
+        let before = with_board(0, |board| board.storage_areas[0].data.clone());
+        store.compact().unwrap();
+        with_board(0, |board| {
+            board.storage_areas[0].data[64..64 + HEADER_LENGTH]
+                .copy_from_slice(&before[64..64 + HEADER_LENGTH])
+        });
+        let store = KvStore::open(&area).unwrap();
+        assert_eq!(store.get("first"), Some(&[1][..]));
+        assert_eq!(store.get("second"), Some(&[2][..]));
+    }
+
+    #[test]
+    fn records_and_compactions_span_blocks() {
+        let _lock = test_lock();
+        install_blocked_area(8);
+        let library = Congatec::new().unwrap();
+        let board = library.get_board(BoardClass::ALL, 0).unwrap();
+        let area = board.get_storage_area_from_index(0).unwrap();
+        let mut store = KvStore::format(&area).unwrap();
+        // Larger than a block, the header already pushes it across a block boundary.
+        store.set("large", &[0xaa; 20]).unwrap();
+        for counter in 0..10u8 {
+            store.set("counter", &[counter]).unwrap();
+        }
+        let store = KvStore::open(&area).unwrap();
+        assert_eq!(store.get("large"), Some(&[0xaa; 20][..]));
+        assert_eq!(store.get("counter"), Some(&[9][..]));
+    }
+}
@@ -1,7 +1,51 @@
+#[cfg(feature = "unsafe-bindings")]
+pub mod bindings;
+#[cfg(not(feature = "unsafe-bindings"))]
 mod bindings;
 pub mod board;
+#[cfg(feature = "capture")]
+pub mod capture;
+pub mod cgbc;
+#[cfg(feature = "config-store")]
+pub mod config_store;
 pub mod congatec;
+#[cfg(feature = "storage")]
+mod crc;
+#[cfg(all(feature = "dbus", target_os = "linux"))]
+pub mod dbus;
+pub mod device;
+pub mod diagnostics;
+#[cfg(all(feature = "dynamic", not(feature = "mock")))]
+mod dynamic;
+pub mod error;
+#[cfg(feature = "sensors")]
 pub mod fan;
+#[cfg(feature = "embedded-hal")]
+mod hal;
+#[cfg(feature = "io")]
+pub mod io;
+#[cfg(feature = "storage")]
+pub mod kv_store;
+#[cfg(feature = "mock")]
+pub mod mock;
+pub mod owned;
+pub mod performance;
+#[cfg(feature = "prometheus")]
+pub mod prometheus;
+pub mod recovery;
+pub mod self_test;
+#[cfg(feature = "sensors")]
+pub mod sensor;
 pub mod status;
+#[cfg(feature = "storage")]
 pub mod storage_area;
+#[cfg(feature = "tokio")]
+pub mod stream;
+#[cfg(all(feature = "systemd", target_os = "linux"))]
+pub mod systemd;
+#[cfg(feature = "sensors")]
 pub mod temperature;
+#[cfg(feature = "sensors")]
+pub mod voltage;
+#[cfg(feature = "wdog")]
+pub mod watchdog;
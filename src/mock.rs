@@ -0,0 +1,283 @@
+//! Simulated boards replacing libcgos, enabled with the `mock` feature.
+//!
+//! Install the hardware to simulate with [`install`] before opening the library, then use the
+//! crate as usual. [`with_board`] inspects or changes a simulated board while it is in use, e.g.
+//! to move a sensor reading or to check how often a watchdog was triggered.
+//!
+//! With the `mock-fixtures` feature the boards can also be described in JSON or TOML, see
+//! [`install_fixture`].
+
+use std::{
+    collections::BTreeMap,
+    sync::{Mutex, MutexGuard, PoisonError},
+    time::{Duration, Instant},
+};
+
+#[cfg(feature = "mock-fixtures")]
+use serde::Deserialize;
+
+use crate::{
+    board::BoardClass,
+    status::Status,
+    storage_area::StorageKind,
+    watchdog::{WatchdogConfig, WatchdogMode, WatchdogOperatingMode},
+};
+
+pub(crate) mod ffi;
+#[cfg(feature = "mock-fixtures")]
+mod fixture;
+
+#[cfg(feature = "mock-fixtures")]
+pub use fixture::{install_fixture, install_json, install_toml};
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "mock-fixtures", derive(Deserialize), serde(default))]
+pub struct MockBoard {
+    pub name: String,
+    #[cfg_attr(
+        feature = "mock-fixtures",
+        serde(deserialize_with = "fixture::board_class")
+    )]
+    pub classes: BoardClass,
+    pub manufacturer: String,
+    pub serial_number: String,
+    pub part_number: String,
+    pub boot_count: u32,
+    pub running_time_hours: u32,
+    pub options: BTreeMap<u32, u32>,
+    pub boot_error_log: Vec<u8>,
+    pub temperatures: Vec<MockSensor>,
+    pub fans: Vec<MockSensor>,
+    pub voltages: Vec<MockSensor>,
+    pub storage_areas: Vec<MockStorageArea>,
+    pub io_ports: Vec<MockIoPort>,
+    pub watchdogs: Vec<MockWatchdog>,
+    /// Indexed by performance unit.
+    pub performance: Vec<MockPerformance>,
+}
+
+impl Default for MockBoard {
+    fn default() -> Self {
+        Self {
+            name: "MOCK".to_string(),
+            classes: BoardClass::CPU,
+            manufacturer: String::new(),
+            serial_number: String::new(),
+            part_number: String::new(),
+            boot_count: 0,
+            running_time_hours: 0,
+            options: BTreeMap::new(),
+            boot_error_log: Vec::new(),
+            temperatures: Vec::new(),
+            fans: Vec::new(),
+            voltages: Vec::new(),
+            storage_areas: Vec::new(),
+            io_ports: Vec::new(),
+            watchdogs: Vec::new(),
+            performance: Vec::new(),
+        }
+    }
+}
+
+/// Temperature, fan or voltage sensor in raw CGOS units (1/1000 °C, RPM or mV).
+///
+/// The alarm flag is reported while the value is outside the alarm limits, a limit of 0 is
+/// disabled.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "mock-fixtures", derive(Deserialize), serde(default))]
+pub struct MockSensor {
+    /// One of the `CGOS_TEMP_*`, `CGOS_FAN_*` or `CGOS_VOLTAGE_*` type values.
+    #[cfg_attr(feature = "mock-fixtures", serde(rename = "type"))]
+    pub type_: u32,
+    /// Reported until the first step of `script` is reached.
+    pub value: i32,
+    /// Changes of the value over the time since [`install`], ordered by time.
+    pub script: Vec<MockStep>,
+    #[cfg_attr(feature = "mock-fixtures", serde(deserialize_with = "fixture::status"))]
+    pub status: Status,
+    pub nominal: i32,
+    pub resolution: i32,
+    pub minimum: i32,
+    pub maximum: i32,
+    pub alarm_high: i32,
+    pub hysteresis_high: i32,
+    pub alarm_low: i32,
+    pub hysteresis_low: i32,
+    pub output_minimum: i32,
+    pub output_maximum: i32,
+}
+
+impl Default for MockSensor {
+    fn default() -> Self {
+        Self {
+            type_: 0,
+            value: 0,
+            script: Vec::new(),
+            status: Status::ACTIVE,
+            nominal: 0,
+            resolution: 0,
+            minimum: 0,
+            maximum: 0,
+            alarm_high: 0,
+            hysteresis_high: 0,
+            alarm_low: 0,
+            hysteresis_low: 0,
+            output_minimum: 0,
+            output_maximum: 0,
+        }
+    }
+}
+
+impl MockSensor {
+    pub(crate) fn current_value(&self, elapsed: Duration) -> i32 {
+        self.script
+            .iter()
+            .take_while(|step| step.time <= elapsed)
+            .last()
+            .map_or(self.value, |step| step.value)
+    }
+
+    pub(crate) fn current_status(&self, elapsed: Duration) -> Status {
+        let value = self.current_value(elapsed);
+        let too_high = self.alarm_high != 0 && value > self.alarm_high;
+        let too_low = self.alarm_low != 0 && value < self.alarm_low;
+        let mut status = self.status;
+        status.set(Status::ALARM, too_high || too_low);
+        status
+    }
+}
+
+/// Sensor value reported from `time` after [`install`] on.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "mock-fixtures", derive(Deserialize))]
+pub struct MockStep {
+    #[cfg_attr(
+        feature = "mock-fixtures",
+        serde(rename = "time_ms", deserialize_with = "fixture::milliseconds")
+    )]
+    pub time: Duration,
+    pub value: i32,
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "mock-fixtures",
+    derive(Deserialize),
+    serde(from = "fixture::StorageAreaFixture")
+)]
+pub struct MockStorageArea {
+    pub kind: StorageKind,
+    pub data: Vec<u8>,
+    /// Reads and writes crossing a multiple of this fail, like on flash-backed areas. 0 for no
+    /// limit.
+    pub block_size: usize,
+    /// Secret set by the last lock, `None` while unlocked.
+    pub secret: Option<Vec<u8>>,
+}
+
+impl MockStorageArea {
+    /// Erased area (all bytes 0xff) of `size` bytes.
+    pub fn new(kind: StorageKind, size: usize) -> Self {
+        Self {
+            kind,
+            data: vec![0xff; size],
+            block_size: 0,
+            secret: None,
+        }
+    }
+}
+
+/// GPIO port, set bits in `direction` are inputs like in CGOS.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "mock-fixtures", derive(Deserialize), serde(default))]
+pub struct MockIoPort {
+    pub name: String,
+    pub inputs: u32,
+    pub outputs: u32,
+    pub direction: u32,
+    pub value: u32,
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "mock-fixtures", derive(Deserialize), serde(default))]
+pub struct MockWatchdog {
+    /// Not part of fixtures, watchdogs always start disabled there.
+    #[cfg_attr(feature = "mock-fixtures", serde(skip))]
+    pub config: WatchdogConfig,
+    pub triggers: usize,
+}
+
+impl Default for MockWatchdog {
+    fn default() -> Self {
+        Self {
+            config: WatchdogConfig {
+                timeout: Duration::ZERO,
+                delay: Duration::ZERO,
+                mode: WatchdogMode::RebootPc,
+                operating_mode: WatchdogOperatingMode::Disabled,
+                stages: Vec::new(),
+            },
+            triggers: 0,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "mock-fixtures", derive(Deserialize), serde(default))]
+pub struct MockPerformance {
+    pub current: u32,
+    pub policy: u32,
+    pub policy_caps: u32,
+}
+
+pub(crate) struct State {
+    pub(crate) initialized: bool,
+    pub(crate) boards: Vec<MockBoard>,
+    pub(crate) handles: BTreeMap<u32, usize>,
+    pub(crate) next_handle: u32,
+    pub(crate) last_error: u32,
+    pub(crate) installed: Option<Instant>,
+}
+
+static STATE: Mutex<State> = Mutex::new(State {
+    initialized: false,
+    boards: Vec::new(),
+    handles: BTreeMap::new(),
+    next_handle: 1,
+    last_error: 0,
+    installed: None,
+});
+
+pub(crate) fn state() -> MutexGuard<'static, State> {
+    STATE.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Replaces the simulated hardware. Boards opened before stay valid only if their index still is.
+///
+/// Sensor scripts start over at every install.
+pub fn install(boards: Vec<MockBoard>) {
+    let mut state = state();
+    state.boards = boards;
+    state.installed = Some(Instant::now());
+}
+
+/// Invalidates every open board handle like a driver reload, calls on them fail from then on.
+pub fn reload_driver() {
+    state().handles.clear();
+}
+
+/// Held by tests of the crate, they share the simulated hardware.
+#[cfg(test)]
+pub(crate) fn test_lock() -> MutexGuard<'static, ()> {
+    static LOCK: Mutex<()> = Mutex::new(());
+    LOCK.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Runs `function` on the simulated board at `index`.
+///
+/// # Panics
+///
+/// Panics if no board with this index is installed.
+pub fn with_board<Return>(index: usize, function: impl FnOnce(&mut MockBoard) -> Return) -> Return {
+    function(&mut state().boards[index])
+}
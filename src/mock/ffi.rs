@@ -0,0 +1,1096 @@
+//! Hand-written CGOS types and constants plus simulated implementations of the library calls.
+
+#![allow(clippy::all)]
+#![allow(dead_code)]
+#![allow(non_upper_case_globals)]
+#![allow(non_camel_case_types)]
+#![allow(non_snake_case)]
+
+use std::{
+    ffi::{c_char, c_uchar, c_uint, c_void, CStr},
+    mem::zeroed,
+    slice,
+    time::Duration,
+};
+
+use super::{state, MockBoard, MockSensor, MockStorageArea};
+use crate::watchdog::{WatchdogConfig, WatchdogOperatingMode};
+
+/// Reported by CgosLibGetLastError for every failed mock call.
+const ERROR_INVALID_PARAMETER: u32 = 1;
+const VERSION: u32 = 0x0102_0000;
+
+pub type cgosret_bool = ::std::os::raw::c_uint;
+pub type HCGOS = ::std::os::raw::c_uint;
+
+pub const CGOS_BOARD_CLASS_CPU: u32 = 65536;
+pub const CGOS_BOARD_CLASS_VGA: u32 = 131072;
+pub const CGOS_BOARD_CLASS_IO: u32 = 262144;
+pub const CGOS_BOARD_MAX_SIZE_ID_STRING: u32 = 16;
+pub const CGOS_BOARD_MAX_SIZE_SERIAL_STRING: u32 = 16;
+pub const CGOS_BOARD_MAX_SIZE_PART_STRING: u32 = 16;
+pub const CGOS_BOARD_MAX_SIZE_EAN_STRING: u32 = 16;
+
+pub const CGOS_SENSOR_ACTIVE: u32 = 1;
+pub const CGOS_SENSOR_ALARM: u32 = 2;
+pub const CGOS_SENSOR_BROKEN: u32 = 4;
+pub const CGOS_SENSOR_SHORTCIRCUIT: u32 = 8;
+
+pub const CGOS_TEMP_CPU: u32 = 65536;
+pub const CGOS_TEMP_BOX: u32 = 131072;
+pub const CGOS_TEMP_ENV: u32 = 196608;
+pub const CGOS_TEMP_BOARD: u32 = 262144;
+pub const CGOS_TEMP_BACKPLANE: u32 = 327680;
+pub const CGOS_TEMP_CHIPSETS: u32 = 393216;
+pub const CGOS_TEMP_VIDEO: u32 = 458752;
+pub const CGOS_TEMP_OTHER: u32 = 524288;
+pub const CGOS_TEMP_TOPDIMM_ENV: u32 = 589824;
+pub const CGOS_TEMP_BOTDIMM_ENV: u32 = 655360;
+
+pub const CGOS_FAN_CPU: u32 = 65536;
+pub const CGOS_FAN_BOX: u32 = 131072;
+pub const CGOS_FAN_ENV: u32 = 196608;
+pub const CGOS_FAN_CHIPSET: u32 = 262144;
+pub const CGOS_FAN_VIDEO: u32 = 327680;
+pub const CGOS_FAN_OTHER: u32 = 393216;
+
+pub const CGOS_VOLTAGE_CPU_CORE: u32 = 65536;
+pub const CGOS_VOLTAGE_CPU_IO: u32 = 131072;
+pub const CGOS_VOLTAGE_DC: u32 = 196608;
+pub const CGOS_VOLTAGE_DC_STANDBY: u32 = 262144;
+pub const CGOS_VOLTAGE_BAT_CMOS: u32 = 327680;
+pub const CGOS_VOLTAGE_BAT_POWER: u32 = 393216;
+pub const CGOS_VOLTAGE_AC: u32 = 458752;
+pub const CGOS_VOLTAGE_OTHER: u32 = 524288;
+pub const CGOS_VOLTAGE_5V_S0: u32 = 589824;
+pub const CGOS_VOLTAGE_5V_S5: u32 = 655360;
+pub const CGOS_VOLTAGE_33V_S0: u32 = 720896;
+pub const CGOS_VOLTAGE_33V_S5: u32 = 786432;
+pub const CGOS_VOLTAGE_VCOREA: u32 = 851968;
+pub const CGOS_VOLTAGE_VCOREB: u32 = 917504;
+pub const CGOS_VOLTAGE_12V_S0: u32 = 983040;
+
+pub const CGOS_STORAGE_AREA_UNKNOWN: u32 = 0;
+pub const CGOS_STORAGE_AREA_EEPROM: u32 = 65536;
+pub const CGOS_STORAGE_AREA_FLASH: u32 = 131072;
+pub const CGOS_STORAGE_AREA_CMOS: u32 = 196608;
+pub const CGOS_STORAGE_AREA_RAM: u32 = 262144;
+
+pub const CGOS_WDOG_MODE_REBOOT_PC: u32 = 0;
+pub const CGOS_WDOG_MODE_RESTART_OS: u32 = 1;
+pub const CGOS_WDOG_MODE_STAGED: u32 = 128;
+pub const CGOS_WDOG_OPMODE_DISABLED: u32 = 0;
+pub const CGOS_WDOG_OPMODE_ONETIME_TRIG: u32 = 1;
+pub const CGOS_WDOG_OPMODE_SINGLE_EVENT: u32 = 2;
+pub const CGOS_WDOG_OPMODE_EVENT_REPEAT: u32 = 3;
+pub const CGOS_WDOG_EVENT_INT: u32 = 0;
+pub const CGOS_WDOG_EVENT_SCI: u32 = 1;
+pub const CGOS_WDOG_EVENT_RST: u32 = 2;
+pub const CGOS_WDOG_EVENT_BTN: u32 = 3;
+pub const CGOS_WDOG_EXPIRE_STAGES: u32 = 3;
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct CGOSTIME {
+    pub wYear: ::std::os::raw::c_ushort,
+    pub wMonth: ::std::os::raw::c_ushort,
+    pub wDayOfWeek: ::std::os::raw::c_ushort,
+    pub wDay: ::std::os::raw::c_ushort,
+    pub wHour: ::std::os::raw::c_ushort,
+    pub wMinute: ::std::os::raw::c_ushort,
+    pub wSecond: ::std::os::raw::c_ushort,
+    pub wMilliseconds: ::std::os::raw::c_ushort,
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct CGOSBOARDINFOA {
+    pub dwSize: ::std::os::raw::c_uint,
+    pub dwFlags: ::std::os::raw::c_uint,
+    pub szReserved: [::std::os::raw::c_char; 16usize],
+    pub szBoard: [::std::os::raw::c_char; 16usize],
+    pub szBoardSub: [::std::os::raw::c_char; 16usize],
+    pub szManufacturer: [::std::os::raw::c_char; 16usize],
+    pub stManufacturingDate: CGOSTIME,
+    pub stLastRepairDate: CGOSTIME,
+    pub szSerialNumber: [::std::os::raw::c_char; 16usize],
+    pub wProductRevision: ::std::os::raw::c_ushort,
+    pub wSystemBiosRevision: ::std::os::raw::c_ushort,
+    pub wBiosInterfaceRevision: ::std::os::raw::c_ushort,
+    pub wBiosInterfaceBuildRevision: ::std::os::raw::c_ushort,
+    pub dwClasses: ::std::os::raw::c_uint,
+    pub dwPrimaryClass: ::std::os::raw::c_uint,
+    pub dwRepairCounter: ::std::os::raw::c_uint,
+    pub szPartNumber: [::std::os::raw::c_char; 16usize],
+    pub szEAN: [::std::os::raw::c_char; 16usize],
+    pub dwManufacturer: ::std::os::raw::c_uint,
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct CGOSTEMPERATUREINFO {
+    pub dwSize: ::std::os::raw::c_uint,
+    pub dwType: ::std::os::raw::c_uint,
+    pub dwFlags: ::std::os::raw::c_uint,
+    pub dwAlarm: ::std::os::raw::c_uint,
+    pub dwRes: ::std::os::raw::c_uint,
+    pub dwMin: ::std::os::raw::c_uint,
+    pub dwMax: ::std::os::raw::c_uint,
+    pub dwAlarmHi: ::std::os::raw::c_uint,
+    pub dwHystHi: ::std::os::raw::c_uint,
+    pub dwAlarmLo: ::std::os::raw::c_uint,
+    pub dwHystLo: ::std::os::raw::c_uint,
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct CGOSFANINFO {
+    pub dwSize: ::std::os::raw::c_uint,
+    pub dwType: ::std::os::raw::c_uint,
+    pub dwFlags: ::std::os::raw::c_uint,
+    pub dwAlarm: ::std::os::raw::c_uint,
+    pub dwSpeedNom: ::std::os::raw::c_uint,
+    pub dwMin: ::std::os::raw::c_uint,
+    pub dwMax: ::std::os::raw::c_uint,
+    pub dwAlarmHi: ::std::os::raw::c_uint,
+    pub dwHystHi: ::std::os::raw::c_uint,
+    pub dwAlarmLo: ::std::os::raw::c_uint,
+    pub dwHystLo: ::std::os::raw::c_uint,
+    pub dwOutMin: ::std::os::raw::c_uint,
+    pub dwOutMax: ::std::os::raw::c_uint,
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct CGOSVOLTAGEINFO {
+    pub dwSize: ::std::os::raw::c_uint,
+    pub dwType: ::std::os::raw::c_uint,
+    pub dwNom: ::std::os::raw::c_uint,
+    pub dwFlags: ::std::os::raw::c_uint,
+    pub dwAlarm: ::std::os::raw::c_uint,
+    pub dwRes: ::std::os::raw::c_uint,
+    pub dwMin: ::std::os::raw::c_uint,
+    pub dwMax: ::std::os::raw::c_uint,
+    pub dwAlarmHi: ::std::os::raw::c_uint,
+    pub dwHystHi: ::std::os::raw::c_uint,
+    pub dwAlarmLo: ::std::os::raw::c_uint,
+    pub dwHystLo: ::std::os::raw::c_uint,
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct CGOSWDINFO {
+    pub dwSize: ::std::os::raw::c_uint,
+    pub dwFlags: ::std::os::raw::c_uint,
+    pub dwMinTimeout: ::std::os::raw::c_uint,
+    pub dwMaxTimeout: ::std::os::raw::c_uint,
+    pub dwMinDelay: ::std::os::raw::c_uint,
+    pub dwMaxDelay: ::std::os::raw::c_uint,
+    pub dwOpModes: ::std::os::raw::c_uint,
+    pub dwMaxStageCount: ::std::os::raw::c_uint,
+    pub dwEvents: ::std::os::raw::c_uint,
+    pub dwType: ::std::os::raw::c_uint,
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct CGOSWDSTAGE {
+    pub dwTimeout: ::std::os::raw::c_uint,
+    pub dwEvent: ::std::os::raw::c_uint,
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct CGOSWDCONFIG {
+    pub dwSize: ::std::os::raw::c_uint,
+    pub dwTimeout: ::std::os::raw::c_uint,
+    pub dwDelay: ::std::os::raw::c_uint,
+    pub dwMode: ::std::os::raw::c_uint,
+    pub dwOpMode: ::std::os::raw::c_uint,
+    pub dwStageCount: ::std::os::raw::c_uint,
+    pub stStages: [CGOSWDSTAGE; 3usize],
+}
+
+fn fail() -> cgosret_bool {
+    state().last_error = ERROR_INVALID_PARAMETER;
+    0
+}
+
+/// Runs `function` on the board behind `handle`, failing the call if either doesn't succeed.
+///
+/// `function` also gets the time since the boards were installed, to evaluate sensor scripts.
+fn call(
+    handle: HCGOS,
+    function: impl FnOnce(&mut MockBoard, Duration) -> Option<()>,
+) -> cgosret_bool {
+    let succeeded = {
+        let mut state = state();
+        let elapsed = state
+            .installed
+            .map_or(Duration::ZERO, |installed| installed.elapsed());
+        match state.handles.get(&handle).copied() {
+            Some(index) => function(&mut state.boards[index], elapsed).is_some(),
+            None => false,
+        }
+    };
+    match succeeded {
+        true => 1,
+        false => fail(),
+    }
+}
+
+/// Like `call()` for functions returning a value, which is 0 for invalid handles.
+fn query(handle: HCGOS, function: impl FnOnce(&MockBoard) -> u32) -> c_uint {
+    let state = state();
+    match state.handles.get(&handle) {
+        Some(&index) => function(&state.boards[index]),
+        None => 0,
+    }
+}
+
+fn boards_of_class(boards: &[MockBoard], class: c_uint) -> impl Iterator<Item = usize> + '_ {
+    boards
+        .iter()
+        .enumerate()
+        .filter(move |(_, board)| class == 0 || board.classes.bits() & class != 0)
+        .map(|(index, _)| index)
+}
+
+fn open(index: usize, handle: *mut HCGOS) -> cgosret_bool {
+    let mut state = state();
+    let next = state.next_handle;
+    state.next_handle += 1;
+    state.handles.insert(next, index);
+    unsafe { *handle = next };
+    1
+}
+
+unsafe fn copy_string(string: &str, destination: *mut c_char, size: usize) {
+    let length = string.len().min(size.saturating_sub(1));
+    let destination = slice::from_raw_parts_mut(destination as *mut u8, size);
+    destination[..length].copy_from_slice(&string.as_bytes()[..length]);
+    if size > 0 {
+        destination[length] = 0;
+    }
+}
+
+fn c_chars<const SIZE: usize>(string: &str) -> [c_char; SIZE] {
+    let mut chars = [0; SIZE];
+    for (char, &byte) in chars.iter_mut().zip(string.as_bytes()) {
+        *char = byte as c_char;
+    }
+    chars
+}
+
+pub unsafe fn CgosLibGetVersion() -> c_uint {
+    VERSION
+}
+
+pub unsafe fn CgosLibInitialize() -> cgosret_bool {
+    state().initialized = true;
+    1
+}
+
+pub unsafe fn CgosLibUninitialize() -> cgosret_bool {
+    let initialized = std::mem::replace(&mut state().initialized, false);
+    match initialized {
+        true => 1,
+        false => fail(),
+    }
+}
+
+pub unsafe fn CgosLibIsAvailable() -> cgosret_bool {
+    state().initialized.into()
+}
+
+pub unsafe fn CgosLibInstall(_install: c_uint) -> cgosret_bool {
+    1
+}
+
+pub unsafe fn CgosLibGetDrvVersion() -> c_uint {
+    VERSION
+}
+
+pub unsafe fn CgosLibGetLastError() -> c_uint {
+    state().last_error
+}
+
+pub unsafe fn CgosLibSetLastErrorAddress(_pErrNo: *mut c_uint) -> cgosret_bool {
+    1
+}
+
+pub unsafe fn CgosBoardCount(dwClass: c_uint, _dwFlags: c_uint) -> c_uint {
+    boards_of_class(&state().boards, dwClass).count() as c_uint
+}
+
+pub unsafe fn CgosBoardOpen(
+    dwClass: c_uint,
+    dwNum: c_uint,
+    _dwFlags: c_uint,
+    phCgos: *mut HCGOS,
+) -> cgosret_bool {
+    let index = boards_of_class(&state().boards, dwClass).nth(dwNum as usize);
+    match index {
+        Some(index) => open(index, phCgos),
+        None => fail(),
+    }
+}
+
+pub unsafe fn CgosBoardOpenByNameA(pszName: *const c_char, phCgos: *mut HCGOS) -> cgosret_bool {
+    let name = CStr::from_ptr(pszName).to_string_lossy();
+    let index = state().boards.iter().position(|board| board.name == name);
+    match index {
+        Some(index) => open(index, phCgos),
+        None => fail(),
+    }
+}
+
+pub unsafe fn CgosBoardClose(hCgos: HCGOS) -> cgosret_bool {
+    let closed = state().handles.remove(&hCgos).is_some();
+    match closed {
+        true => 1,
+        false => fail(),
+    }
+}
+
+pub unsafe fn CgosBoardGetNameA(
+    hCgos: HCGOS,
+    pszName: *mut c_char,
+    dwSize: c_uint,
+) -> cgosret_bool {
+    call(hCgos, |board, _| {
+        copy_string(&board.name, pszName, dwSize as usize);
+        Some(())
+    })
+}
+
+pub unsafe fn CgosBoardGetInfoA(hCgos: HCGOS, pBoardInfo: *mut CGOSBOARDINFOA) -> cgosret_bool {
+    call(hCgos, |board, _| {
+        let mut info: CGOSBOARDINFOA = zeroed();
+        info.dwSize = (*pBoardInfo).dwSize;
+        info.szBoard = c_chars(&board.name);
+        info.szManufacturer = c_chars(&board.manufacturer);
+        info.szSerialNumber = c_chars(&board.serial_number);
+        info.szPartNumber = c_chars(&board.part_number);
+        info.dwClasses = board.classes.bits();
+        info.dwPrimaryClass = board.classes.bits();
+        *pBoardInfo = info;
+        Some(())
+    })
+}
+
+pub unsafe fn CgosBoardGetBootCounter(hCgos: HCGOS, pdwCount: *mut c_uint) -> cgosret_bool {
+    call(hCgos, |board, _| {
+        *pdwCount = board.boot_count;
+        Some(())
+    })
+}
+
+pub unsafe fn CgosBoardGetRunningTimeMeter(hCgos: HCGOS, pdwCount: *mut c_uint) -> cgosret_bool {
+    call(hCgos, |board, _| {
+        *pdwCount = board.running_time_hours;
+        Some(())
+    })
+}
+
+pub unsafe fn CgosBoardGetOption(
+    hCgos: HCGOS,
+    dwOption: c_uint,
+    pdwSetting: *mut c_uint,
+) -> cgosret_bool {
+    call(hCgos, |board, _| {
+        *pdwSetting = *board.options.get(&dwOption)?;
+        Some(())
+    })
+}
+
+pub unsafe fn CgosBoardSetOption(
+    hCgos: HCGOS,
+    dwOption: c_uint,
+    dwSetting: c_uint,
+) -> cgosret_bool {
+    call(hCgos, |board, _| {
+        board.options.insert(dwOption, dwSetting);
+        Some(())
+    })
+}
+
+pub unsafe fn CgosBoardGetBootErrorLog(
+    hCgos: HCGOS,
+    _dwType: c_uint,
+    pdwLogType: *mut c_uint,
+    pBytes: *mut c_uchar,
+    pdwLen: *mut c_uint,
+) -> cgosret_bool {
+    call(hCgos, |board, _| {
+        let length = board.boot_error_log.len().min(*pdwLen as usize);
+        slice::from_raw_parts_mut(pBytes, length).copy_from_slice(&board.boot_error_log[..length]);
+        *pdwLogType = 0;
+        *pdwLen = length as c_uint;
+        Some(())
+    })
+}
+
+/// Storage area units are either an index or a `CGOS_STORAGE_AREA_*` type selecting the first
+/// area of that type.
+fn storage_area(board: &mut MockBoard, unit: c_uint) -> Option<&mut MockStorageArea> {
+    if unit < CGOS_STORAGE_AREA_EEPROM {
+        return board.storage_areas.get_mut(unit as usize);
+    }
+    let kind = unit.into();
+    board
+        .storage_areas
+        .iter_mut()
+        .find(|area| area.kind == kind)
+}
+
+/// Like `storage_range()`, also failing transfers that don't stay within one block.
+fn transfer_range(
+    area: &MockStorageArea,
+    offset: c_uint,
+    length: c_uint,
+) -> Option<std::ops::Range<usize>> {
+    let range = storage_range(area, offset, length)?;
+    let block = |offset: usize| offset / area.block_size;
+    if area.block_size != 0 && !range.is_empty() && block(range.start) != block(range.end - 1) {
+        return None;
+    }
+    Some(range)
+}
+
+fn storage_range(
+    area: &MockStorageArea,
+    offset: c_uint,
+    length: c_uint,
+) -> Option<std::ops::Range<usize>> {
+    let start = offset as usize;
+    let end = start.checked_add(length as usize)?;
+    (end <= area.data.len()).then_some(start..end)
+}
+
+pub unsafe fn CgosStorageAreaCount(hCgos: HCGOS, dwUnit: c_uint) -> c_uint {
+    query(hCgos, |board| {
+        let kind = dwUnit.into();
+        board
+            .storage_areas
+            .iter()
+            .filter(|area| dwUnit == CGOS_STORAGE_AREA_UNKNOWN || area.kind == kind)
+            .count() as c_uint
+    })
+}
+
+pub unsafe fn CgosStorageAreaType(hCgos: HCGOS, dwUnit: c_uint) -> c_uint {
+    query(hCgos, |board| {
+        let mut board = board.clone();
+        storage_area(&mut board, dwUnit).map_or(0, |area| area.kind.into())
+    })
+}
+
+pub unsafe fn CgosStorageAreaSize(hCgos: HCGOS, dwUnit: c_uint) -> c_uint {
+    query(hCgos, |board| {
+        let mut board = board.clone();
+        storage_area(&mut board, dwUnit).map_or(0, |area| area.data.len() as c_uint)
+    })
+}
+
+pub unsafe fn CgosStorageAreaBlockSize(hCgos: HCGOS, dwUnit: c_uint) -> c_uint {
+    query(hCgos, |board| {
+        let mut board = board.clone();
+        storage_area(&mut board, dwUnit).map_or(0, |area| area.block_size as c_uint)
+    })
+}
+
+pub unsafe fn CgosStorageAreaRead(
+    hCgos: HCGOS,
+    dwUnit: c_uint,
+    dwOffset: c_uint,
+    pBytes: *mut c_uchar,
+    dwLen: c_uint,
+) -> cgosret_bool {
+    call(hCgos, |board, _| {
+        let area = storage_area(board, dwUnit)?;
+        let range = transfer_range(area, dwOffset, dwLen)?;
+        slice::from_raw_parts_mut(pBytes, range.len()).copy_from_slice(&area.data[range]);
+        Some(())
+    })
+}
+
+pub unsafe fn CgosStorageAreaWrite(
+    hCgos: HCGOS,
+    dwUnit: c_uint,
+    dwOffset: c_uint,
+    pBytes: *mut c_uchar,
+    dwLen: c_uint,
+) -> cgosret_bool {
+    call(hCgos, |board, _| {
+        let area = storage_area(board, dwUnit)?;
+        let range = transfer_range(area, dwOffset, dwLen)?;
+        if area.secret.is_some() {
+            return None;
+        }
+        area.data[range.clone()].copy_from_slice(slice::from_raw_parts(pBytes, range.len()));
+        Some(())
+    })
+}
+
+pub unsafe fn CgosStorageAreaErase(
+    hCgos: HCGOS,
+    dwUnit: c_uint,
+    dwOffset: c_uint,
+    dwLen: c_uint,
+) -> cgosret_bool {
+    call(hCgos, |board, _| {
+        let area = storage_area(board, dwUnit)?;
+        let range = storage_range(area, dwOffset, dwLen)?;
+        if area.secret.is_some() {
+            return None;
+        }
+        area.data[range].fill(0xff);
+        Some(())
+    })
+}
+
+/// Erasing completes immediately, so the status is always successful.
+pub unsafe fn CgosStorageAreaEraseStatus(
+    hCgos: HCGOS,
+    dwUnit: c_uint,
+    _dwOffset: c_uint,
+    _dwLen: c_uint,
+    lpStatus: *mut c_uint,
+) -> cgosret_bool {
+    call(hCgos, |board, _| {
+        storage_area(board, dwUnit)?;
+        *lpStatus = 0;
+        Some(())
+    })
+}
+
+pub unsafe fn CgosStorageAreaLock(
+    hCgos: HCGOS,
+    dwUnit: c_uint,
+    _dwFlags: c_uint,
+    pBytes: *mut c_uchar,
+    dwLen: c_uint,
+) -> cgosret_bool {
+    call(hCgos, |board, _| {
+        let area = storage_area(board, dwUnit)?;
+        area.secret = Some(slice::from_raw_parts(pBytes, dwLen as usize).to_vec());
+        Some(())
+    })
+}
+
+pub unsafe fn CgosStorageAreaUnlock(
+    hCgos: HCGOS,
+    dwUnit: c_uint,
+    _dwFlags: c_uint,
+    pBytes: *mut c_uchar,
+    dwLen: c_uint,
+) -> cgosret_bool {
+    call(hCgos, |board, _| {
+        let area = storage_area(board, dwUnit)?;
+        let secret = slice::from_raw_parts(pBytes, dwLen as usize);
+        if matches!(area.secret.as_deref(), Some(locked) if locked != secret) {
+            return None;
+        }
+        area.secret = None;
+        Some(())
+    })
+}
+
+pub unsafe fn CgosStorageAreaIsLocked(
+    hCgos: HCGOS,
+    dwUnit: c_uint,
+    _dwFlags: c_uint,
+) -> cgosret_bool {
+    query(hCgos, |board| {
+        let mut board = board.clone();
+        storage_area(&mut board, dwUnit).map_or(0, |area| area.secret.is_some().into())
+    })
+}
+
+pub unsafe fn CgosIOCount(hCgos: HCGOS) -> c_uint {
+    query(hCgos, |board| board.io_ports.len() as c_uint)
+}
+
+pub unsafe fn CgosIOIsAvailable(hCgos: HCGOS, dwUnit: c_uint) -> cgosret_bool {
+    query(hCgos, |board| {
+        (dwUnit < board.io_ports.len() as c_uint).into()
+    })
+}
+
+pub unsafe fn CgosIORead(hCgos: HCGOS, dwUnit: c_uint, pdwData: *mut c_uint) -> cgosret_bool {
+    call(hCgos, |board, _| {
+        *pdwData = board.io_ports.get(dwUnit as usize)?.value;
+        Some(())
+    })
+}
+
+/// Only bits configured as outputs change.
+pub unsafe fn CgosIOWrite(hCgos: HCGOS, dwUnit: c_uint, dwData: c_uint) -> cgosret_bool {
+    call(hCgos, |board, _| {
+        let port = board.io_ports.get_mut(dwUnit as usize)?;
+        let outputs = !port.direction;
+        port.value = port.value & !outputs | dwData & outputs;
+        Some(())
+    })
+}
+
+pub unsafe fn CgosIOXorAndXor(
+    hCgos: HCGOS,
+    dwUnit: c_uint,
+    dwXorMask1: c_uint,
+    dwAndMask: c_uint,
+    dwXorMask2: c_uint,
+) -> cgosret_bool {
+    call(hCgos, |board, _| {
+        let port = board.io_ports.get_mut(dwUnit as usize)?;
+        let outputs = !port.direction;
+        let value = ((port.value ^ dwXorMask1) & dwAndMask) ^ dwXorMask2;
+        port.value = port.value & !outputs | value & outputs;
+        Some(())
+    })
+}
+
+pub unsafe fn CgosIOGetDirectionCaps(
+    hCgos: HCGOS,
+    dwUnit: c_uint,
+    pdwInputs: *mut c_uint,
+    pdwOutputs: *mut c_uint,
+) -> cgosret_bool {
+    call(hCgos, |board, _| {
+        let port = board.io_ports.get(dwUnit as usize)?;
+        *pdwInputs = port.inputs;
+        *pdwOutputs = port.outputs;
+        Some(())
+    })
+}
+
+pub unsafe fn CgosIOGetDirection(
+    hCgos: HCGOS,
+    dwUnit: c_uint,
+    pdwData: *mut c_uint,
+) -> cgosret_bool {
+    call(hCgos, |board, _| {
+        *pdwData = board.io_ports.get(dwUnit as usize)?.direction;
+        Some(())
+    })
+}
+
+pub unsafe fn CgosIOSetDirection(hCgos: HCGOS, dwUnit: c_uint, dwData: c_uint) -> cgosret_bool {
+    call(hCgos, |board, _| {
+        board.io_ports.get_mut(dwUnit as usize)?.direction = dwData;
+        Some(())
+    })
+}
+
+pub unsafe fn CgosIOGetNameA(
+    hCgos: HCGOS,
+    dwUnit: c_uint,
+    pszName: *mut c_char,
+    dwSize: c_uint,
+) -> cgosret_bool {
+    call(hCgos, |board, _| {
+        copy_string(
+            &board.io_ports.get(dwUnit as usize)?.name,
+            pszName,
+            dwSize as usize,
+        );
+        Some(())
+    })
+}
+
+pub unsafe fn CgosWDogCount(hCgos: HCGOS) -> c_uint {
+    query(hCgos, |board| board.watchdogs.len() as c_uint)
+}
+
+pub unsafe fn CgosWDogIsAvailable(hCgos: HCGOS, dwUnit: c_uint) -> cgosret_bool {
+    query(hCgos, |board| {
+        (dwUnit < board.watchdogs.len() as c_uint).into()
+    })
+}
+
+pub unsafe fn CgosWDogTrigger(hCgos: HCGOS, dwUnit: c_uint) -> cgosret_bool {
+    call(hCgos, |board, _| {
+        board.watchdogs.get_mut(dwUnit as usize)?.triggers += 1;
+        Some(())
+    })
+}
+
+pub unsafe fn CgosWDogGetConfigStruct(
+    hCgos: HCGOS,
+    dwUnit: c_uint,
+    pConfig: *mut CGOSWDCONFIG,
+) -> cgosret_bool {
+    call(hCgos, |board, _| {
+        let watchdog = board.watchdogs.get(dwUnit as usize)?;
+        *pConfig = CGOSWDCONFIG::try_from(&watchdog.config).ok()?;
+        Some(())
+    })
+}
+
+pub unsafe fn CgosWDogSetConfigStruct(
+    hCgos: HCGOS,
+    dwUnit: c_uint,
+    pConfig: *mut CGOSWDCONFIG,
+) -> cgosret_bool {
+    call(hCgos, |board, _| {
+        board.watchdogs.get_mut(dwUnit as usize)?.config = (*pConfig).into();
+        Some(())
+    })
+}
+
+pub unsafe fn CgosWDogSetConfig(
+    hCgos: HCGOS,
+    dwUnit: c_uint,
+    timeout: c_uint,
+    delay: c_uint,
+    mode: c_uint,
+) -> cgosret_bool {
+    call(hCgos, |board, _| {
+        let watchdog = board.watchdogs.get_mut(dwUnit as usize)?;
+        watchdog.config = WatchdogConfig {
+            timeout: Duration::from_millis(timeout.into()),
+            delay: Duration::from_millis(delay.into()),
+            mode: mode.into(),
+            operating_mode: WatchdogOperatingMode::SingleEvent,
+            stages: Vec::new(),
+        };
+        Some(())
+    })
+}
+
+pub unsafe fn CgosWDogDisable(hCgos: HCGOS, dwUnit: c_uint) -> cgosret_bool {
+    call(hCgos, |board, _| {
+        board
+            .watchdogs
+            .get_mut(dwUnit as usize)?
+            .config
+            .operating_mode = WatchdogOperatingMode::Disabled;
+        Some(())
+    })
+}
+
+pub unsafe fn CgosWDogGetInfo(
+    hCgos: HCGOS,
+    dwUnit: c_uint,
+    pInfo: *mut CGOSWDINFO,
+) -> cgosret_bool {
+    call(hCgos, |board, _| {
+        board.watchdogs.get(dwUnit as usize)?;
+        let mut info: CGOSWDINFO = zeroed();
+        info.dwSize = (*pInfo).dwSize;
+        info.dwMaxTimeout = u32::MAX;
+        info.dwMaxDelay = u32::MAX;
+        info.dwMaxStageCount = CGOS_WDOG_EXPIRE_STAGES;
+        *pInfo = info;
+        Some(())
+    })
+}
+
+unsafe fn sensor_current(
+    sensor: Option<&MockSensor>,
+    elapsed: Duration,
+    pdwSetting: *mut c_uint,
+    pdwStatus: *mut c_uint,
+) -> Option<()> {
+    let sensor = sensor?;
+    *pdwSetting = sensor.current_value(elapsed) as c_uint;
+    *pdwStatus = sensor.current_status(elapsed).bits();
+    Some(())
+}
+
+fn set_sensor_limits(
+    sensor: &mut MockSensor,
+    alarm_high: u32,
+    hysteresis_high: u32,
+    alarm_low: u32,
+    hysteresis_low: u32,
+) {
+    sensor.alarm_high = alarm_high as i32;
+    sensor.hysteresis_high = hysteresis_high as i32;
+    sensor.alarm_low = alarm_low as i32;
+    sensor.hysteresis_low = hysteresis_low as i32;
+}
+
+pub unsafe fn CgosTemperatureCount(hCgos: HCGOS) -> c_uint {
+    query(hCgos, |board| board.temperatures.len() as c_uint)
+}
+
+pub unsafe fn CgosTemperatureGetInfo(
+    hCgos: HCGOS,
+    dwUnit: c_uint,
+    pInfo: *mut CGOSTEMPERATUREINFO,
+) -> cgosret_bool {
+    call(hCgos, |board, elapsed| {
+        let sensor = board.temperatures.get(dwUnit as usize)?;
+        *pInfo = CGOSTEMPERATUREINFO {
+            dwSize: (*pInfo).dwSize,
+            dwType: sensor.type_,
+            dwFlags: sensor.current_status(elapsed).bits(),
+            dwAlarm: 0,
+            dwRes: sensor.resolution as c_uint,
+            dwMin: sensor.minimum as c_uint,
+            dwMax: sensor.maximum as c_uint,
+            dwAlarmHi: sensor.alarm_high as c_uint,
+            dwHystHi: sensor.hysteresis_high as c_uint,
+            dwAlarmLo: sensor.alarm_low as c_uint,
+            dwHystLo: sensor.hysteresis_low as c_uint,
+        };
+        Some(())
+    })
+}
+
+pub unsafe fn CgosTemperatureGetCurrent(
+    hCgos: HCGOS,
+    dwUnit: c_uint,
+    pdwSetting: *mut c_uint,
+    pdwStatus: *mut c_uint,
+) -> cgosret_bool {
+    call(hCgos, |board, elapsed| {
+        sensor_current(
+            board.temperatures.get(dwUnit as usize),
+            elapsed,
+            pdwSetting,
+            pdwStatus,
+        )
+    })
+}
+
+pub unsafe fn CgosTemperatureSetLimits(
+    hCgos: HCGOS,
+    dwUnit: c_uint,
+    pInfo: *mut CGOSTEMPERATUREINFO,
+) -> cgosret_bool {
+    call(hCgos, |board, _| {
+        let info = &*pInfo;
+        let sensor = board.temperatures.get_mut(dwUnit as usize)?;
+        set_sensor_limits(
+            sensor,
+            info.dwAlarmHi,
+            info.dwHystHi,
+            info.dwAlarmLo,
+            info.dwHystLo,
+        );
+        Some(())
+    })
+}
+
+pub unsafe fn CgosFanCount(hCgos: HCGOS) -> c_uint {
+    query(hCgos, |board| board.fans.len() as c_uint)
+}
+
+pub unsafe fn CgosFanGetInfo(
+    hCgos: HCGOS,
+    dwUnit: c_uint,
+    pInfo: *mut CGOSFANINFO,
+) -> cgosret_bool {
+    call(hCgos, |board, elapsed| {
+        let sensor = board.fans.get(dwUnit as usize)?;
+        *pInfo = CGOSFANINFO {
+            dwSize: (*pInfo).dwSize,
+            dwType: sensor.type_,
+            dwFlags: sensor.current_status(elapsed).bits(),
+            dwAlarm: 0,
+            dwSpeedNom: sensor.nominal as c_uint,
+            dwMin: sensor.minimum as c_uint,
+            dwMax: sensor.maximum as c_uint,
+            dwAlarmHi: sensor.alarm_high as c_uint,
+            dwHystHi: sensor.hysteresis_high as c_uint,
+            dwAlarmLo: sensor.alarm_low as c_uint,
+            dwHystLo: sensor.hysteresis_low as c_uint,
+            dwOutMin: sensor.output_minimum as c_uint,
+            dwOutMax: sensor.output_maximum as c_uint,
+        };
+        Some(())
+    })
+}
+
+pub unsafe fn CgosFanGetCurrent(
+    hCgos: HCGOS,
+    dwUnit: c_uint,
+    pdwSetting: *mut c_uint,
+    pdwStatus: *mut c_uint,
+) -> cgosret_bool {
+    call(hCgos, |board, elapsed| {
+        sensor_current(
+            board.fans.get(dwUnit as usize),
+            elapsed,
+            pdwSetting,
+            pdwStatus,
+        )
+    })
+}
+
+pub unsafe fn CgosFanSetLimits(
+    hCgos: HCGOS,
+    dwUnit: c_uint,
+    pInfo: *mut CGOSFANINFO,
+) -> cgosret_bool {
+    call(hCgos, |board, _| {
+        let info = &*pInfo;
+        let sensor = board.fans.get_mut(dwUnit as usize)?;
+        set_sensor_limits(
+            sensor,
+            info.dwAlarmHi,
+            info.dwHystHi,
+            info.dwAlarmLo,
+            info.dwHystLo,
+        );
+        sensor.output_minimum = info.dwOutMin as i32;
+        sensor.output_maximum = info.dwOutMax as i32;
+        Some(())
+    })
+}
+
+pub unsafe fn CgosVoltageCount(hCgos: HCGOS) -> c_uint {
+    query(hCgos, |board| board.voltages.len() as c_uint)
+}
+
+pub unsafe fn CgosVoltageGetInfo(
+    hCgos: HCGOS,
+    dwUnit: c_uint,
+    pInfo: *mut CGOSVOLTAGEINFO,
+) -> cgosret_bool {
+    call(hCgos, |board, elapsed| {
+        let sensor = board.voltages.get(dwUnit as usize)?;
+        *pInfo = CGOSVOLTAGEINFO {
+            dwSize: (*pInfo).dwSize,
+            dwType: sensor.type_,
+            dwNom: sensor.nominal as c_uint,
+            dwFlags: sensor.current_status(elapsed).bits(),
+            dwAlarm: 0,
+            dwRes: sensor.resolution as c_uint,
+            dwMin: sensor.minimum as c_uint,
+            dwMax: sensor.maximum as c_uint,
+            dwAlarmHi: sensor.alarm_high as c_uint,
+            dwHystHi: sensor.hysteresis_high as c_uint,
+            dwAlarmLo: sensor.alarm_low as c_uint,
+            dwHystLo: sensor.hysteresis_low as c_uint,
+        };
+        Some(())
+    })
+}
+
+pub unsafe fn CgosVoltageGetCurrent(
+    hCgos: HCGOS,
+    dwUnit: c_uint,
+    pdwSetting: *mut c_uint,
+    pdwStatus: *mut c_uint,
+) -> cgosret_bool {
+    call(hCgos, |board, elapsed| {
+        sensor_current(
+            board.voltages.get(dwUnit as usize),
+            elapsed,
+            pdwSetting,
+            pdwStatus,
+        )
+    })
+}
+
+pub unsafe fn CgosVoltageSetLimits(
+    hCgos: HCGOS,
+    dwUnit: c_uint,
+    pInfo: *mut CGOSVOLTAGEINFO,
+) -> cgosret_bool {
+    call(hCgos, |board, _| {
+        let info = &*pInfo;
+        let sensor = board.voltages.get_mut(dwUnit as usize)?;
+        set_sensor_limits(
+            sensor,
+            info.dwAlarmHi,
+            info.dwHystHi,
+            info.dwAlarmLo,
+            info.dwHystLo,
+        );
+        Some(())
+    })
+}
+
+pub unsafe fn CgosPerformanceGetCurrent(
+    hCgos: HCGOS,
+    dwUnit: c_uint,
+    pdwSetting: *mut c_uint,
+) -> cgosret_bool {
+    call(hCgos, |board, _| {
+        *pdwSetting = board.performance.get(dwUnit as usize)?.current;
+        Some(())
+    })
+}
+
+pub unsafe fn CgosPerformanceSetCurrent(
+    hCgos: HCGOS,
+    dwUnit: c_uint,
+    dwSetting: c_uint,
+) -> cgosret_bool {
+    call(hCgos, |board, _| {
+        board.performance.get_mut(dwUnit as usize)?.current = dwSetting;
+        Some(())
+    })
+}
+
+pub unsafe fn CgosPerformanceGetPolicyCaps(
+    hCgos: HCGOS,
+    dwUnit: c_uint,
+    pdwSetting: *mut c_uint,
+) -> cgosret_bool {
+    call(hCgos, |board, _| {
+        *pdwSetting = board.performance.get(dwUnit as usize)?.policy_caps;
+        Some(())
+    })
+}
+
+pub unsafe fn CgosPerformanceGetPolicy(
+    hCgos: HCGOS,
+    dwUnit: c_uint,
+    pdwSetting: *mut c_uint,
+) -> cgosret_bool {
+    call(hCgos, |board, _| {
+        *pdwSetting = board.performance.get(dwUnit as usize)?.policy;
+        Some(())
+    })
+}
+
+pub unsafe fn CgosPerformanceSetPolicy(
+    hCgos: HCGOS,
+    dwUnit: c_uint,
+    dwSetting: c_uint,
+) -> cgosret_bool {
+    call(hCgos, |board, _| {
+        board.performance.get_mut(dwUnit as usize)?.policy = dwSetting;
+        Some(())
+    })
+}
+
+/// The board controller is not simulated, its calls fail.
+pub unsafe fn CgosCgbcGetInfo(_hCgos: HCGOS, _dwType: c_uint, _pInfo: *mut c_void) -> cgosret_bool {
+    fail()
+}
+
+pub unsafe fn CgosCgbcSetControl(
+    _hCgos: HCGOS,
+    _dwLine: c_uint,
+    _dwSetting: c_uint,
+) -> cgosret_bool {
+    fail()
+}
+
+pub unsafe fn CgosCgbcReadWrite(
+    _hCgos: HCGOS,
+    _bDataByte: c_uchar,
+    _pDataByte: *mut c_uchar,
+    _dwClockDelay: c_uint,
+    _dwTimeoutDelay: c_uint,
+) -> cgosret_bool {
+    fail()
+}
+
+pub unsafe fn CgosCgbcHandleCommand(
+    _hCgos: HCGOS,
+    _pBytesWrite: *mut c_uchar,
+    _dwLenWrite: c_uint,
+    _pBytesRead: *mut c_uchar,
+    _dwLenRead: c_uint,
+    _pdwStatus: *mut c_uint,
+) -> cgosret_bool {
+    fail()
+}
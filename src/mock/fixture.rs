@@ -0,0 +1,177 @@
+use std::{fs::read_to_string, path::Path, time::Duration};
+
+use serde::{Deserialize, Deserializer};
+
+use super::{install, MockBoard, MockStorageArea};
+use crate::{board::BoardClass, error::Result, status::Status};
+
+/// Top level of a fixture, a list of boards so that TOML can describe them as `[[boards]]`.
+#[derive(Deserialize)]
+struct Fixture {
+    boards: Vec<MockBoard>,
+}
+
+/// Installs the boards described by a JSON fixture.
+///
+/// Fields left out take their [`Default`] values, raw CGOS values like sensor types, board
+/// classes, status flags and storage area types are given as numbers:
+///
+/// ```json
+/// {
+///   "boards": [{
+///     "name": "conga-TC570",
+///     "temperatures": [{ "type": 65536, "value": 45000, "script": [{ "time_ms": 1000, "value": 90000 }] }],
+///     "storage_areas": [{ "type": 65536, "size": 256, "data": [1, 2, 3] }]
+///   }]
+/// }
+/// ```
+pub fn install_json(json: &str) -> Result<()> {
+    let fixture: Fixture = serde_json::from_str(json)?;
+    install(fixture.boards);
+    Ok(())
+}
+
+/// Installs the boards described by a TOML fixture, with the same structure as for
+/// [`install_json`].
+pub fn install_toml(toml: &str) -> Result<()> {
+    let fixture: Fixture = toml::from_str(toml)?;
+    install(fixture.boards);
+    Ok(())
+}
+
+/// Installs the boards described by a fixture file, TOML for a `.toml` extension and JSON
+/// otherwise.
+pub fn install_fixture(path: impl AsRef<Path>) -> Result<()> {
+    let path = path.as_ref();
+    let contents = read_to_string(path)?;
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("toml") => install_toml(&contents),
+        _ => install_json(&contents),
+    }
+}
+
+/// Storage area as written in fixtures, `data` is padded to `size` with erased bytes.
+#[derive(Deserialize)]
+pub(super) struct StorageAreaFixture {
+    #[serde(rename = "type")]
+    kind: u32,
+    #[serde(default)]
+    size: usize,
+    #[serde(default)]
+    data: Vec<u8>,
+    #[serde(default)]
+    block_size: usize,
+    #[serde(default)]
+    secret: Option<Vec<u8>>,
+}
+
+impl From<StorageAreaFixture> for MockStorageArea {
+    fn from(fixture: StorageAreaFixture) -> Self {
+        let mut area =
+            MockStorageArea::new(fixture.kind.into(), fixture.size.max(fixture.data.len()));
+        area.data[..fixture.data.len()].copy_from_slice(&fixture.data);
+        area.block_size = fixture.block_size;
+        area.secret = fixture.secret;
+        area
+    }
+}
+
+pub(super) fn board_class<'de, D>(deserializer: D) -> std::result::Result<BoardClass, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    u32::deserialize(deserializer).map(BoardClass::from_bits_truncate)
+}
+
+pub(super) fn status<'de, D>(deserializer: D) -> std::result::Result<Status, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    u32::deserialize(deserializer).map(Status::from_bits_truncate)
+}
+
+pub(super) fn milliseconds<'de, D>(deserializer: D) -> std::result::Result<Duration, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    u64::deserialize(deserializer).map(Duration::from_millis)
+}
+
+#[cfg(all(test, feature = "mock-fixtures"))]
+mod tests {
+    use std::{env::temp_dir, fs::write, process};
+
+    use super::*;
+    use crate::{
+        congatec::Congatec, error::CgosError, mock::test_lock, storage_area::StorageKind,
+        temperature::Celsius,
+    };
+
+    const JSON: &str = r#"{
+        "boards": [{
+            "name": "conga-TC570",
+            "temperatures": [{
+                "type": 65536,
+                "value": 45000,
+                "script": [{ "time_ms": 0, "value": 50000 }, { "time_ms": 3600000, "value": 90000 }]
+            }],
+            "storage_areas": [{ "type": 65536, "size": 8, "data": [1, 2, 3] }]
+        }]
+    }"#;
+
+    const TOML: &str = r#"
+        [[boards]]
+        name = "conga-TC570"
+
+        [[boards.temperatures]]
+        type = 65536
+        value = 45000
+        script = [{ time_ms = 0, value = 50000 }, { time_ms = 3600000, value = 90000 }]
+
+        [[boards.storage_areas]]
+        type = 65536
+        size = 8
+        data = [1, 2, 3]
+    "#;
+
+    fn check_installed_board() {
+        let library = Congatec::new().unwrap();
+        let board = library.get_board(BoardClass::ALL, 0).unwrap();
+        assert_eq!(board.name().unwrap(), "conga-TC570");
+        let (celsius, _) = board.get_temperature(0).unwrap().current().unwrap();
+        assert_eq!(celsius, Celsius(50.0));
+        let area = board
+            .get_storage_area_from_kind(StorageKind::Eeprom)
+            .unwrap();
+        assert_eq!(
+            area.read_all().unwrap(),
+            [1, 2, 3, 0xff, 0xff, 0xff, 0xff, 0xff]
+        );
+    }
+
+    #[test]
+    fn json_fixture_installs_boards() {
+        let _lock = test_lock();
+        install_json(JSON).unwrap();
+        check_installed_board();
+    }
+
+    #[test]
+    fn toml_fixture_installs_boards() {
+        let _lock = test_lock();
+        install_toml(TOML).unwrap();
+        check_installed_board();
+    }
+
+    #[test]
+    fn fixture_file_format_follows_extension() {
+        let _lock = test_lock();
+        let path = temp_dir().join(format!("cgos-fixture-{}.toml", process::id()));
+        write(&path, TOML).unwrap();
+        let result = install_fixture(&path);
+        let _ = std::fs::remove_file(&path);
+        result.unwrap();
+        check_installed_board();
+        assert!(matches!(install_json(TOML), Err(CgosError::Json(_))));
+    }
+}
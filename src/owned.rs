@@ -0,0 +1,130 @@
+//! Boards and devices owning the library through an [`Arc`], so they are `'static` and can be
+//! stored in long-lived structs or moved into threads and tasks.
+
+use std::{
+    ops::{Deref, DerefMut},
+    sync::Arc,
+};
+
+#[cfg(feature = "io")]
+use crate::io::IoPort;
+#[cfg(feature = "storage")]
+use crate::storage_area::{StorageArea, StorageKind};
+#[cfg(feature = "wdog")]
+use crate::watchdog::Watchdog;
+use crate::{
+    board::{Board, BoardClass},
+    cgbc::Cgbc,
+    congatec::Congatec,
+    error::Result,
+    performance::Performance,
+};
+#[cfg(feature = "sensors")]
+use crate::{fan::Fan, temperature::Temperature, voltage::Voltage};
+
+/// Board keeping its library open, devices opened from it keep the board open in turn.
+pub struct SharedBoard {
+    // Declared first to close the board before the library is dropped.
+    board: Board<'static>,
+    _library: Arc<Congatec>,
+}
+
+impl SharedBoard {
+    pub fn open(library: Arc<Congatec>, class: BoardClass, index: usize) -> Result<Arc<Self>> {
+        Ok(Arc::new(Self {
+            board: Board::new(class, index)?,
+            _library: library,
+        }))
+    }
+
+    pub fn from_name(library: Arc<Congatec>, name: &str) -> Result<Arc<Self>> {
+        Ok(Arc::new(Self {
+            board: Board::from_name(name)?,
+            _library: library,
+        }))
+    }
+
+    fn own<Device>(self: &Arc<Self>, device: Device) -> Owned<Device> {
+        Owned {
+            device,
+            _board: self.clone(),
+        }
+    }
+
+    pub fn get_cgbc(self: &Arc<Self>) -> Owned<Cgbc<'static>> {
+        self.own(Cgbc::new(self.board.raw_handle()))
+    }
+
+    #[cfg(feature = "sensors")]
+    pub fn get_temperature(self: &Arc<Self>, index: usize) -> Result<Owned<Temperature<'static>>> {
+        Ok(self.own(Temperature::new(self.board.raw_handle(), index)?))
+    }
+
+    #[cfg(feature = "sensors")]
+    pub fn get_fan(self: &Arc<Self>, index: usize) -> Result<Owned<Fan<'static>>> {
+        Ok(self.own(Fan::new(self.board.raw_handle(), index)?))
+    }
+
+    #[cfg(feature = "sensors")]
+    pub fn get_voltage(self: &Arc<Self>, index: usize) -> Result<Owned<Voltage<'static>>> {
+        Ok(self.own(Voltage::new(self.board.raw_handle(), index)?))
+    }
+
+    pub fn get_performance(self: &Arc<Self>, unit: u32) -> Owned<Performance<'static>> {
+        self.own(Performance::new(self.board.raw_handle(), unit))
+    }
+
+    #[cfg(feature = "io")]
+    pub fn get_io_port(self: &Arc<Self>, index: usize) -> Result<Owned<IoPort<'static>>> {
+        Ok(self.own(IoPort::new(self.board.raw_handle(), index)?))
+    }
+
+    #[cfg(feature = "storage")]
+    pub fn get_storage_area_from_index(
+        self: &Arc<Self>,
+        index: usize,
+    ) -> Result<Owned<StorageArea<'static>>> {
+        Ok(self.own(StorageArea::from_index(self.board.raw_handle(), index)?))
+    }
+
+    #[cfg(feature = "storage")]
+    pub fn get_storage_area_from_kind(
+        self: &Arc<Self>,
+        kind: StorageKind,
+    ) -> Result<Owned<StorageArea<'static>>> {
+        Ok(self.own(StorageArea::from_kind(self.board.raw_handle(), kind)?))
+    }
+
+    #[cfg(feature = "wdog")]
+    pub fn get_watchdog(self: &Arc<Self>, index: usize) -> Result<Owned<Watchdog<'static>>> {
+        Ok(self.own(Watchdog::new(self.board.raw_handle(), index)?))
+    }
+}
+
+impl Deref for SharedBoard {
+    type Target = Board<'static>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.board
+    }
+}
+
+/// Device keeping the board it was opened from open.
+pub struct Owned<Device> {
+    device: Device,
+    _board: Arc<SharedBoard>,
+}
+
+impl<Device> Deref for Owned<Device> {
+    type Target = Device;
+
+    fn deref(&self) -> &Self::Target {
+        &self.device
+    }
+}
+
+impl<Device> DerefMut for Owned<Device> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.device
+    }
+}
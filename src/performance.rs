@@ -0,0 +1,86 @@
+use std::marker::PhantomData;
+
+use crate::{
+    bindings::{
+        CgosPerformanceGetCurrent, CgosPerformanceGetPolicy, CgosPerformanceGetPolicyCaps,
+        CgosPerformanceSetCurrent, CgosPerformanceSetPolicy,
+    },
+    error::{ffi_call, Result},
+};
+
+/// Performance control of one unit (e.g. the CPU) of a board.
+pub struct Performance<'library> {
+    handle: u32,
+    unit: u32,
+    _library_lifetime: PhantomData<&'library ()>,
+}
+
+impl<'library> Performance<'library> {
+    pub(crate) fn new(handle: u32, unit: u32) -> Performance<'library> {
+        Self {
+            handle,
+            unit,
+            _library_lifetime: PhantomData,
+        }
+    }
+
+    /// Board handle and performance unit to pass to CGOS functions directly.
+    pub fn raw_parts(&self) -> (u32, u32) {
+        (self.handle, self.unit)
+    }
+
+    /// # Safety
+    ///
+    /// `handle` has to stay open for `'library` and `unit` is not validated.
+    pub unsafe fn from_raw_parts(handle: u32, unit: u32) -> Self {
+        Self {
+            handle,
+            unit,
+            _library_lifetime: PhantomData,
+        }
+    }
+
+    pub fn current(&self) -> Result<u32> {
+        let mut setting = 0;
+        ffi_call!(CgosPerformanceGetCurrent(
+            self.handle,
+            self.unit,
+            &mut setting
+        ))?;
+        Ok(setting)
+    }
+
+    /// Levels the unit doesn't support are rejected by the firmware.
+    pub fn set_current(&self, setting: u32) -> Result<()> {
+        ffi_call!(CgosPerformanceSetCurrent(self.handle, self.unit, setting))
+    }
+
+    pub fn policy(&self) -> Result<PerformancePolicy> {
+        let mut setting = 0;
+        ffi_call!(CgosPerformanceGetPolicy(
+            self.handle,
+            self.unit,
+            &mut setting
+        ))?;
+        Ok(PerformancePolicy(setting))
+    }
+
+    pub fn set_policy(&self, policy: PerformancePolicy) -> Result<()> {
+        ffi_call!(CgosPerformanceSetPolicy(self.handle, self.unit, policy.0))
+    }
+
+    /// Raw capability word as reported by the firmware.
+    pub fn policy_caps(&self) -> Result<u32> {
+        let mut caps = 0;
+        ffi_call!(CgosPerformanceGetPolicyCaps(
+            self.handle,
+            self.unit,
+            &mut caps
+        ))?;
+        Ok(caps)
+    }
+}
+
+/// Policy value as documented for the board, CGOS itself defines none.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PerformancePolicy(pub u32);
@@ -0,0 +1,126 @@
+use prometheus::{Gauge, GaugeVec, IntGauge, IntGaugeVec, Opts, Registry};
+
+use crate::{board::Board, error::Result, status::Status};
+
+/// Prometheus gauges for all sensors, the boot counter and the running time meter of a board.
+///
+/// Gauges are registered once and refreshed from the board by [`Self::update`], e.g. right before
+/// each scrape. Sensors are labelled with their index and type.
+pub struct PrometheusExporter<'board, 'library> {
+    board: &'board Board<'library>,
+    temperature_types: Vec<String>,
+    fan_types: Vec<String>,
+    voltage_types: Vec<String>,
+    temperatures: GaugeVec,
+    fans: GaugeVec,
+    voltages: GaugeVec,
+    alarms: IntGaugeVec,
+    boot_count: IntGauge,
+    running_time: Gauge,
+}
+
+impl<'board, 'library> PrometheusExporter<'board, 'library> {
+    pub fn register(board: &'board Board<'library>, registry: &Registry) -> Result<Self> {
+        let sensor_labels = ["index", "type"];
+        let exporter = Self {
+            board,
+            temperature_types: (0..board.get_number_of_temperatures())
+                .map(|index| Ok(format!("{:?}", board.get_temperature(index)?.info()?.type_)))
+                .collect::<Result<_>>()?,
+            fan_types: (0..board.get_number_of_fans())
+                .map(|index| Ok(format!("{:?}", board.get_fan(index)?.info()?.type_)))
+                .collect::<Result<_>>()?,
+            voltage_types: (0..board.get_number_of_voltages())
+                .map(|index| Ok(format!("{:?}", board.get_voltage(index)?.info()?.type_)))
+                .collect::<Result<_>>()?,
+            temperatures: GaugeVec::new(
+                Opts::new("cgos_temperature_celsius", "Temperature sensor reading"),
+                &sensor_labels,
+            )?,
+            fans: GaugeVec::new(Opts::new("cgos_fan_rpm", "Fan speed"), &sensor_labels)?,
+            voltages: GaugeVec::new(
+                Opts::new("cgos_voltage_volts", "Voltage sensor reading"),
+                &sensor_labels,
+            )?,
+            alarms: IntGaugeVec::new(
+                Opts::new("cgos_sensor_alarm", "Whether a sensor reports an alarm"),
+                &["sensor", "index", "type"],
+            )?,
+            boot_count: IntGauge::new("cgos_boot_count", "Number of boots of the board")?,
+            running_time: Gauge::new(
+                "cgos_running_time_seconds",
+                "Total running time of the board",
+            )?,
+        };
+        registry.register(Box::new(exporter.temperatures.clone()))?;
+        registry.register(Box::new(exporter.fans.clone()))?;
+        registry.register(Box::new(exporter.voltages.clone()))?;
+        registry.register(Box::new(exporter.alarms.clone()))?;
+        registry.register(Box::new(exporter.boot_count.clone()))?;
+        registry.register(Box::new(exporter.running_time.clone()))?;
+        Ok(exporter)
+    }
+
+    /// Reads all sensors and counters. Values that fail to read keep their previous value.
+    pub fn update(&self) {
+        for (index, type_) in self.temperature_types.iter().enumerate() {
+            if let Ok((value, status)) = self
+                .board
+                .get_temperature(index)
+                .and_then(|sensor| sensor.current())
+            {
+                self.set(
+                    &self.temperatures,
+                    "temperature",
+                    index,
+                    type_,
+                    value.0,
+                    status,
+                );
+            }
+        }
+        for (index, type_) in self.fan_types.iter().enumerate() {
+            if let Ok((value, status)) = self.board.get_fan(index).and_then(|fan| fan.current()) {
+                self.set(&self.fans, "fan", index, type_, value as f64, status);
+            }
+        }
+        for (index, type_) in self.voltage_types.iter().enumerate() {
+            if let Ok((value, status)) = self
+                .board
+                .get_voltage(index)
+                .and_then(|sensor| sensor.current())
+            {
+                self.set(
+                    &self.voltages,
+                    "voltage",
+                    index,
+                    type_,
+                    value.volts().0,
+                    status,
+                );
+            }
+        }
+        if let Ok(boot_count) = self.board.boot_count() {
+            self.boot_count.set(boot_count as i64);
+        }
+        if let Ok(running_time) = self.board.running_time() {
+            self.running_time.set(running_time.as_secs_f64());
+        }
+    }
+
+    fn set(
+        &self,
+        gauges: &GaugeVec,
+        sensor: &str,
+        index: usize,
+        type_: &str,
+        value: f64,
+        status: Status,
+    ) {
+        let index = index.to_string();
+        gauges.with_label_values(&[&index, type_]).set(value);
+        self.alarms
+            .with_label_values(&[sensor, &index, type_])
+            .set(status.contains(Status::ALARM).into());
+    }
+}
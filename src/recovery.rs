@@ -0,0 +1,117 @@
+//! Opt-in recovery from board handles that died, e.g. after a driver reload or suspend/resume.
+
+use crate::{
+    board::{Board, BoardClass},
+    congatec::Congatec,
+    error::{CgosError, Result},
+};
+
+/// Board that is opened again when an operation on it fails, the operation is retried once.
+///
+/// CGOS reports no distinct error for a dead handle, so every failed CGOS call counts as one.
+pub struct RecoveringBoard<'library> {
+    board: Board<'library>,
+    class: BoardClass,
+    index: usize,
+    on_reopen: Option<ReopenCallback<'library>>,
+}
+
+type ReopenCallback<'library> = Box<dyn FnMut(&Reopened) + Send + 'library>;
+
+/// Reported after a board was opened again.
+#[derive(Debug)]
+pub struct Reopened {
+    pub class: BoardClass,
+    pub index: usize,
+    /// Failure that triggered opening the board again.
+    pub error: CgosError,
+}
+
+impl<'library> RecoveringBoard<'library> {
+    pub fn new(library: &'library Congatec, class: BoardClass, index: usize) -> Result<Self> {
+        Ok(Self {
+            board: library.get_board(class, index)?,
+            class,
+            index,
+            on_reopen: None,
+        })
+    }
+
+    /// Called after every reopen, before the operation is retried.
+    pub fn on_reopen(mut self, callback: impl FnMut(&Reopened) + Send + 'library) -> Self {
+        self.on_reopen = Some(Box::new(callback));
+        self
+    }
+
+    /// Runs `operation` on the board, devices have to be opened inside it to use a fresh handle.
+    pub fn with<Return>(
+        &mut self,
+        mut operation: impl for<'board> FnMut(&'board Board<'board>) -> Result<Return>,
+    ) -> Result<Return> {
+        match operation(&self.board) {
+            Err(error @ CgosError::Failed { .. }) => {
+                self.reopen(error)?;
+                operation(&self.board)
+            }
+            result => result,
+        }
+    }
+
+    fn reopen(&mut self, error: CgosError) -> Result<()> {
+        // Dropping the stale board fails to close its dead handle, which is ignored.
+        self.board = Board::new(self.class, self.index)?;
+        let event = Reopened {
+            class: self.class,
+            index: self.index,
+            error,
+        };
+        #[cfg(feature = "tracing")]
+        tracing::warn!(?event, "reopened CGOS board");
+        if let Some(on_reopen) = &mut self.on_reopen {
+            on_reopen(&event);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use super::*;
+    use crate::mock::{install, reload_driver, test_lock, MockBoard};
+
+    #[test]
+    fn operation_is_retried_after_reopen() {
+        let _lock = test_lock();
+        install(vec![MockBoard::default()]);
+        let library = Congatec::new().unwrap();
+        let reopens = Arc::new(AtomicUsize::new(0));
+        let mut board = RecoveringBoard::new(&library, BoardClass::ALL, 0)
+            .unwrap()
+            .on_reopen({
+                let reopens = reopens.clone();
+                move |_| {
+                    reopens.fetch_add(1, Ordering::Relaxed);
+                }
+            });
+        reload_driver();
+        assert_eq!(board.with(|board| board.name()).unwrap(), "MOCK");
+        assert_eq!(reopens.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn failed_reopen_keeps_dead_board_droppable() {
+        let _lock = test_lock();
+        install(vec![MockBoard::default()]);
+        let library = Congatec::new().unwrap();
+        let mut board = RecoveringBoard::new(&library, BoardClass::ALL, 0).unwrap();
+        reload_driver();
+        install(Vec::new());
+        assert!(board.with(|board| board.name()).is_err());
+        drop(board);
+    }
+}
@@ -0,0 +1,191 @@
+//! Non-destructive check of every subsystem, see [`Congatec::self_test`].
+
+use std::fmt::{self, Display, Formatter};
+
+#[cfg(any(
+    feature = "sensors",
+    feature = "io",
+    feature = "storage",
+    feature = "wdog"
+))]
+use crate::error::Result;
+use crate::{
+    board::{Board, BoardClass},
+    congatec::{Congatec, Version},
+    error::CgosError,
+};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Subsystem {
+    Library,
+    Board,
+    Temperatures,
+    Fans,
+    Voltages,
+    IoPorts,
+    StorageAreas,
+    Watchdogs,
+}
+
+#[derive(Debug)]
+pub struct SubsystemResult {
+    /// Index of the board in [`BoardClass::ALL`], `None` for the library itself.
+    pub board: Option<usize>,
+    pub subsystem: Subsystem,
+    /// Devices of the subsystem that were exercised.
+    pub devices: usize,
+    pub errors: Vec<CgosError>,
+}
+
+impl SubsystemResult {
+    pub fn passed(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct SelfTestReport {
+    pub results: Vec<SubsystemResult>,
+}
+
+impl SelfTestReport {
+    pub fn passed(&self) -> bool {
+        self.results.iter().all(SubsystemResult::passed)
+    }
+
+    pub(crate) fn run(library: &Congatec) -> Self {
+        let mut report = Self::default();
+        // The driver reports no version while it is not loaded.
+        let mut errors = Vec::new();
+        if library.driver_version() == Version::from(0) {
+            errors.push(CgosError::LibraryNotAvailable);
+        }
+        report.push(None, Subsystem::Library, 1, errors);
+        for (index, board) in library.boards(BoardClass::ALL).enumerate() {
+            match board {
+                Ok(board) => report.test_board(index, &board),
+                Err(error) => report.push(Some(index), Subsystem::Board, 1, vec![error]),
+            }
+        }
+        report
+    }
+
+    fn push(
+        &mut self,
+        board: Option<usize>,
+        subsystem: Subsystem,
+        devices: usize,
+        errors: Vec<CgosError>,
+    ) {
+        self.results.push(SubsystemResult {
+            board,
+            subsystem,
+            devices,
+            errors,
+        });
+    }
+
+    /// Runs `test` on every device, collecting the errors.
+    #[cfg(any(
+        feature = "sensors",
+        feature = "io",
+        feature = "storage",
+        feature = "wdog"
+    ))]
+    fn test_devices<Device>(
+        &mut self,
+        board: usize,
+        subsystem: Subsystem,
+        devices: impl Iterator<Item = Result<Device>>,
+        test: impl Fn(&Device) -> Result<()>,
+    ) {
+        let mut amount = 0;
+        let mut errors = Vec::new();
+        for device in devices {
+            amount += 1;
+            if let Err(error) = device.and_then(|device| test(&device)) {
+                errors.push(error);
+            }
+        }
+        self.push(Some(board), subsystem, amount, errors);
+    }
+
+    fn test_board<'library>(&mut self, index: usize, board: &'library Board<'library>) {
+        let errors = [
+            board.name().err(),
+            board.info().err(),
+            board.boot_count().err(),
+            board.running_time().err(),
+        ];
+        self.push(
+            Some(index),
+            Subsystem::Board,
+            1,
+            errors.into_iter().flatten().collect(),
+        );
+        #[cfg(feature = "sensors")]
+        {
+            self.test_devices(
+                index,
+                Subsystem::Temperatures,
+                board.temperatures(),
+                |sensor| {
+                    sensor.info()?;
+                    sensor.current().map(drop)
+                },
+            );
+            self.test_devices(index, Subsystem::Fans, board.fans(), |sensor| {
+                sensor.info()?;
+                sensor.current().map(drop)
+            });
+            self.test_devices(index, Subsystem::Voltages, board.voltages(), |sensor| {
+                sensor.info()?;
+                sensor.current().map(drop)
+            });
+        }
+        #[cfg(feature = "io")]
+        self.test_devices(index, Subsystem::IoPorts, board.io_ports(), |port| {
+            port.direction_caps()?;
+            port.get_direction()?;
+            port.read().map(drop)
+        });
+        #[cfg(feature = "storage")]
+        self.test_devices(
+            index,
+            Subsystem::StorageAreas,
+            board.storage_areas(),
+            |area| {
+                let mut data = vec![0; area.size().min(16)];
+                area.read(0, &mut data)
+            },
+        );
+        #[cfg(feature = "wdog")]
+        self.test_devices(index, Subsystem::Watchdogs, board.watchdogs(), |watchdog| {
+            watchdog.config().map(drop)
+        });
+    }
+}
+
+impl Display for SelfTestReport {
+    /// One line per subsystem, followed by its errors.
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        for result in &self.results {
+            if let Some(board) = result.board {
+                write!(formatter, "board {board} ")?;
+            }
+            let verdict = match result.passed() {
+                true => "passed",
+                false => "FAILED",
+            };
+            writeln!(
+                formatter,
+                "{:?}: {verdict} ({} devices)",
+                result.subsystem, result.devices
+            )?;
+            for error in &result.errors {
+                writeln!(formatter, "    {error}")?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,336 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    marker::PhantomData,
+    sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender},
+    thread::{spawn, JoinHandle},
+    time::Duration,
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::Result,
+    fan::Fan,
+    status::Status,
+    temperature::{Celsius, Temperature},
+    voltage::{Millivolts, Voltage},
+};
+
+/// Any of the hardware monitoring sensors of a board.
+pub enum Sensor<'library> {
+    Temperature(Temperature<'library>),
+    Fan(Fan<'library>),
+    Voltage(Voltage<'library>),
+}
+
+impl<'library> Sensor<'library> {
+    pub fn current(&self) -> Result<(Reading, Status)> {
+        Ok(match self {
+            Sensor::Temperature(temperature) => {
+                let (value, status) = temperature.current()?;
+                (Reading::Temperature(value), status)
+            }
+            Sensor::Fan(fan) => {
+                let (value, status) = fan.current()?;
+                (Reading::Fan(value), status)
+            }
+            Sensor::Voltage(voltage) => {
+                let (value, status) = voltage.current()?;
+                (Reading::Voltage(value), status)
+            }
+        })
+    }
+
+    pub(crate) fn detached(&self) -> Sensor<'static> {
+        match self {
+            Sensor::Temperature(temperature) => Sensor::Temperature(temperature.detached()),
+            Sensor::Fan(fan) => Sensor::Fan(fan.detached()),
+            Sensor::Voltage(voltage) => Sensor::Voltage(voltage.detached()),
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Reading {
+    Temperature(Celsius),
+    /// Fan speed in RPM.
+    Fan(i32),
+    Voltage(Millivolts),
+}
+
+impl Display for Reading {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            Reading::Temperature(celsius) => write!(formatter, "{celsius}"),
+            Reading::Fan(rpm) => write!(formatter, "{rpm} RPM"),
+            Reading::Voltage(millivolts) => write!(formatter, "{millivolts}"),
+        }
+    }
+}
+
+/// Readings of all sensors of a board, indexed like the sensors. Failed readings are `None`.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Default)]
+pub struct HealthSnapshot {
+    pub temperatures: Vec<Option<(Celsius, Status)>>,
+    pub fans: Vec<Option<(i32, Status)>>,
+    pub voltages: Vec<Option<(Millivolts, Status)>>,
+}
+
+impl HealthSnapshot {
+    pub fn any_alarm(&self) -> bool {
+        let statuses = self
+            .temperatures
+            .iter()
+            .map(|reading| reading.map(|(_, status)| status))
+            .chain(
+                self.fans
+                    .iter()
+                    .map(|reading| reading.map(|(_, status)| status)),
+            )
+            .chain(
+                self.voltages
+                    .iter()
+                    .map(|reading| reading.map(|(_, status)| status)),
+            );
+        statuses
+            .flatten()
+            .any(|status| status.contains(Status::ALARM))
+    }
+}
+
+impl Reading {
+    /// Value in the sensor's unit: degrees Celsius, RPM or millivolts.
+    pub fn value(&self) -> f64 {
+        match self {
+            Reading::Temperature(celsius) => celsius.0,
+            Reading::Fan(rpm) => *rpm as f64,
+            Reading::Voltage(millivolts) => millivolts.0 as f64,
+        }
+    }
+}
+
+/// Alarm level for a watched sensor, in the unit of [`Reading::value`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Limit {
+    value: f64,
+    hysteresis: f64,
+    above: bool,
+}
+
+impl Limit {
+    /// Alarm while the reading is above `value`.
+    pub fn above(value: f64) -> Self {
+        Self {
+            value,
+            hysteresis: 0.0,
+            above: true,
+        }
+    }
+
+    /// Alarm while the reading is below `value`.
+    pub fn below(value: f64) -> Self {
+        Self {
+            value,
+            hysteresis: 0.0,
+            above: false,
+        }
+    }
+
+    /// How far the reading has to move back past the limit before the alarm clears.
+    pub fn hysteresis(mut self, hysteresis: f64) -> Self {
+        self.hysteresis = hysteresis;
+        self
+    }
+
+    fn is_crossed(&self, value: f64, alarm: bool) -> bool {
+        match (self.above, alarm) {
+            (true, false) => value > self.value,
+            (true, true) => value < self.value - self.hysteresis,
+            (false, false) => value < self.value,
+            (false, true) => value > self.value + self.hysteresis,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct WatchEvent {
+    /// Position of the sensor in the order it was added to the builder.
+    pub watch: usize,
+    pub reading: Reading,
+    /// Whether the limit is now exceeded or the alarm cleared.
+    pub alarm: bool,
+}
+
+/// Polls sensors in a background thread and reports when they cross their limits.
+///
+/// Polling ends when the watcher is dropped. The thread works on copies of the sensors, so if
+/// the watcher is leaked (e.g. with `mem::forget`) it keeps sampling after the board was closed.
+pub struct SensorWatcher<'library> {
+    stop: Option<Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+    _library_lifetime: PhantomData<&'library ()>,
+}
+
+impl<'library> SensorWatcher<'library> {
+    pub fn builder(interval: Duration) -> SensorWatcherBuilder<'library> {
+        SensorWatcherBuilder {
+            interval,
+            debounce: 1,
+            watches: Vec::new(),
+        }
+    }
+}
+
+impl<'library> Drop for SensorWatcher<'library> {
+    fn drop(&mut self) {
+        drop(self.stop.take());
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+pub struct SensorWatcherBuilder<'library> {
+    interval: Duration,
+    debounce: usize,
+    watches: Vec<(Sensor<'library>, Limit)>,
+}
+
+impl<'library> SensorWatcherBuilder<'library> {
+    /// Number of consecutive samples a crossing has to persist for before it is reported.
+    pub fn debounce(mut self, samples: usize) -> Self {
+        self.debounce = samples.max(1);
+        self
+    }
+
+    pub fn watch(mut self, sensor: Sensor<'library>, limit: Limit) -> Self {
+        self.watches.push((sensor, limit));
+        self
+    }
+
+    /// Starts polling, calling `callback` from the background thread. Failed samples are skipped.
+    pub fn spawn<Callback>(self, mut callback: Callback) -> SensorWatcher<'library>
+    where
+        Callback: FnMut(WatchEvent) + Send + 'static,
+    {
+        let watches: Vec<_> = self
+            .watches
+            .iter()
+            .map(|(sensor, limit)| (sensor.detached(), *limit))
+            .collect();
+        let (interval, debounce) = (self.interval, self.debounce);
+        let (stop, stopped) = channel::<()>();
+        let thread = spawn(move || {
+            let mut states = vec![(false, 0); watches.len()];
+            while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+                for (watch, ((sensor, limit), (alarm, samples))) in
+                    watches.iter().zip(&mut states).enumerate()
+                {
+                    let Ok((reading, _)) = sensor.current() else {
+                        continue;
+                    };
+                    if !limit.is_crossed(reading.value(), *alarm) {
+                        *samples = 0;
+                        continue;
+                    }
+                    *samples += 1;
+                    if *samples >= debounce {
+                        *alarm = !*alarm;
+                        *samples = 0;
+                        callback(WatchEvent {
+                            watch,
+                            reading,
+                            alarm: *alarm,
+                        });
+                    }
+                }
+            }
+        });
+        SensorWatcher {
+            stop: Some(stop),
+            thread: Some(thread),
+            _library_lifetime: PhantomData,
+        }
+    }
+
+    /// Starts polling and sends events on the returned channel instead of calling back.
+    pub fn spawn_channel(self) -> (SensorWatcher<'library>, Receiver<WatchEvent>) {
+        let (sender, receiver) = channel();
+        let watcher = self.spawn(move |event| {
+            let _ = sender.send(event);
+        });
+        (watcher, receiver)
+    }
+}
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+    use std::thread::sleep;
+
+    use super::*;
+    use crate::{
+        board::BoardClass,
+        congatec::Congatec,
+        mock::{install, test_lock, MockBoard, MockSensor, MockStep},
+    };
+
+    #[test]
+    fn watcher_debounces_and_clears_past_hysteresis() {
+        let _lock = test_lock();
+        let step = |milliseconds, value| MockStep {
+            time: Duration::from_millis(milliseconds),
+            value,
+        };
+        install(vec![MockBoard {
+            fans: vec![MockSensor {
+                value: 3000,
+                ..Default::default()
+            }],
+            temperatures: vec![MockSensor {
+                value: 50_000,
+                // A spike shorter than the debounce, a lasting crossing, a drop that stays
+                // within the hysteresis and finally one below it.
+                script: vec![
+                    step(20, 90_000),
+                    step(30, 50_000),
+                    step(100, 90_000),
+                    step(200, 75_000),
+                    step(300, 60_000),
+                ],
+                ..Default::default()
+            }],
+            ..Default::default()
+        }]);
+        let library = Congatec::new().unwrap();
+        let board = library.get_board(BoardClass::ALL, 0).unwrap();
+        let (watcher, events) = SensorWatcher::builder(Duration::from_millis(10))
+            .debounce(4)
+            .watch(Sensor::Fan(board.get_fan(0).unwrap()), Limit::below(1000.0))
+            .watch(
+                Sensor::Temperature(board.get_temperature(0).unwrap()),
+                Limit::above(80.0).hysteresis(10.0),
+            )
+            .spawn_channel();
+        sleep(Duration::from_millis(400));
+        drop(watcher);
+        let events: Vec<_> = events.iter().collect();
+        assert_eq!(
+            events,
+            [
+                WatchEvent {
+                    watch: 1,
+                    reading: Reading::Temperature(Celsius(90.0)),
+                    alarm: true,
+                },
+                WatchEvent {
+                    watch: 1,
+                    reading: Reading::Temperature(Celsius(60.0)),
+                    alarm: false,
+                },
+            ]
+        );
+    }
+}
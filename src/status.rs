@@ -1,4 +1,8 @@
+use std::fmt::{self, Display, Formatter};
+
 use bitflags::bitflags;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::bindings::{
     CGOS_SENSOR_ACTIVE, CGOS_SENSOR_ALARM, CGOS_SENSOR_BROKEN, CGOS_SENSOR_SHORTCIRCUIT,
@@ -12,3 +16,39 @@ bitflags! {
         const SHORT_CIRCUIT = CGOS_SENSOR_SHORTCIRCUIT;
     }
 }
+
+impl Display for Status {
+    /// Set flags in lower case, e.g. "active, alarm".
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        let flags = [
+            (Status::ACTIVE, "active"),
+            (Status::ALARM, "alarm"),
+            (Status::BROKEN, "broken"),
+            (Status::SHORT_CIRCUIT, "short circuit"),
+        ];
+        let names: Vec<_> = flags
+            .iter()
+            .filter(|(flag, _)| self.contains(*flag))
+            .map(|(_, name)| *name)
+            .collect();
+        match names.is_empty() {
+            true => write!(formatter, "inactive"),
+            false => write!(formatter, "{}", names.join(", ")),
+        }
+    }
+}
+
+/// Serialized as the raw CGOS bits.
+#[cfg(feature = "serde")]
+impl Serialize for Status {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        self.bits.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Status {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        u32::deserialize(deserializer).map(Self::from_bits_truncate)
+    }
+}
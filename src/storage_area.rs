@@ -1,15 +1,31 @@
-use std::marker::PhantomData;
+use std::{
+    io::{self, Read, Seek, SeekFrom, Write},
+    iter::from_fn,
+    marker::PhantomData,
+    ops::{Deref, Range},
+    thread::sleep,
+    time::{Duration, Instant},
+};
 
 use bitflags::bitflags;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
-use crate::bindings::{
-    CgosStorageAreaBlockSize, CgosStorageAreaCount, CgosStorageAreaErase,
-    CgosStorageAreaEraseStatus, CgosStorageAreaIsLocked, CgosStorageAreaLock, CgosStorageAreaRead,
-    CgosStorageAreaSize, CgosStorageAreaType, CgosStorageAreaUnlock, CgosStorageAreaWrite,
-    CGOS_STORAGE_AREA_CMOS, CGOS_STORAGE_AREA_EEPROM, CGOS_STORAGE_AREA_FLASH,
-    CGOS_STORAGE_AREA_RAM, CGOS_STORAGE_AREA_UNKNOWN,
+use crate::{
+    bindings::{
+        CgosStorageAreaBlockSize, CgosStorageAreaCount, CgosStorageAreaErase,
+        CgosStorageAreaEraseStatus, CgosStorageAreaIsLocked, CgosStorageAreaLock,
+        CgosStorageAreaRead, CgosStorageAreaSize, CgosStorageAreaType, CgosStorageAreaUnlock,
+        CgosStorageAreaWrite, CGOS_STORAGE_AREA_CMOS, CGOS_STORAGE_AREA_EEPROM,
+        CGOS_STORAGE_AREA_FLASH, CGOS_STORAGE_AREA_RAM, CGOS_STORAGE_AREA_UNKNOWN,
+    },
+    crc::crc32,
+    error::{ffi_call, ffi_value, CgosError, Result},
 };
 
+const ATOMIC_MAGIC: &[u8; 4] = b"CGAW";
+const ATOMIC_HEADER_LENGTH: usize = ATOMIC_MAGIC.len() + 2 * 4;
+
 pub struct StorageArea<'library> {
     handle: u32,
     unit: u32,
@@ -18,14 +34,34 @@ pub struct StorageArea<'library> {
 
 impl<'library> StorageArea<'library> {
     pub(crate) fn amount(handle: u32, type_: StorageAreaType) -> usize {
-        unsafe { CgosStorageAreaCount(handle, type_.bits()) as usize }
+        ffi_value!(CgosStorageAreaCount(handle, type_.bits())) as usize
     }
 
-    pub(crate) fn from_index(handle: u32, index: usize) -> StorageArea<'library> {
-        Self {
+    pub(crate) fn from_index(handle: u32, index: usize) -> Result<StorageArea<'library>> {
+        let amount = Self::amount(handle, StorageAreaType::UNKNOWN);
+        if index >= amount {
+            return Err(CgosError::IndexOutOfRange { index, amount });
+        }
+        Ok(Self {
             handle,
             unit: index.try_into().unwrap(),
             _library_lifetime: PhantomData,
+        })
+    }
+
+    /// Board handle and storage unit to pass to CGOS functions directly.
+    pub fn raw_parts(&self) -> (u32, u32) {
+        (self.handle, self.unit)
+    }
+
+    /// # Safety
+    ///
+    /// `handle` has to stay open for `'library` and `unit` is not validated.
+    pub unsafe fn from_raw_parts(handle: u32, unit: u32) -> Self {
+        Self {
+            handle,
+            unit,
+            _library_lifetime: PhantomData,
         }
     }
 
@@ -37,111 +73,390 @@ impl<'library> StorageArea<'library> {
         }
     }
 
+    pub(crate) fn from_kind(handle: u32, kind: StorageKind) -> Result<StorageArea<'library>> {
+        if ffi_value!(CgosStorageAreaCount(handle, kind.into())) == 0 {
+            return Err(CgosError::StorageAreaNotFound { kind });
+        }
+        Ok(Self {
+            handle,
+            unit: kind.into(),
+            _library_lifetime: PhantomData,
+        })
+    }
+
+    pub fn kind(&self) -> StorageKind {
+        ffi_value!(CgosStorageAreaType(self.handle, self.unit); unit = self.unit).into()
+    }
+
     pub fn type_(&self) -> StorageAreaType {
-        StorageAreaType::from_bits_truncate(unsafe { CgosStorageAreaType(self.handle, self.unit) })
+        StorageAreaType::from_bits_truncate(
+            ffi_value!(CgosStorageAreaType(self.handle, self.unit); unit = self.unit),
+        )
     }
 
     pub fn size(&self) -> usize {
-        unsafe { CgosStorageAreaSize(self.handle, self.unit) as usize }
+        ffi_value!(CgosStorageAreaSize(self.handle, self.unit); unit = self.unit) as usize
     }
 
     pub fn block_size(&self) -> usize {
-        unsafe { CgosStorageAreaBlockSize(self.handle, self.unit) as usize }
-    }
-
-    pub fn read(&self, offset: usize, data: &mut [u8]) {
-        assert_ne!(
-            unsafe {
-                CgosStorageAreaRead(
-                    self.handle,
-                    self.unit,
-                    offset.try_into().unwrap(),
-                    data.as_mut_ptr(),
-                    data.len().try_into().unwrap(),
-                )
-            },
-            0,
-        );
+        ffi_value!(CgosStorageAreaBlockSize(self.handle, self.unit); unit = self.unit) as usize
     }
 
-    pub fn write(&self, offset: usize, data: &[u8]) {
-        assert_ne!(
-            unsafe {
-                CgosStorageAreaWrite(
-                    self.handle,
-                    self.unit,
-                    offset.try_into().unwrap(),
-                    data.as_ptr() as *mut _,
-                    data.len().try_into().unwrap(),
-                )
-            },
-            0,
-        );
+    pub fn read(&self, offset: usize, data: &mut [u8]) -> Result<()> {
+        self.check_bounds(offset, data.len())?;
+        ffi_call!(CgosStorageAreaRead(
+            self.handle,
+            self.unit,
+            offset.try_into().unwrap(),
+            data.as_mut_ptr(),
+            data.len().try_into().unwrap(),
+        ); unit = self.unit, offset, length = data.len())
     }
 
-    pub fn erase(&self, offset: usize, length: usize) {
-        assert_ne!(
-            unsafe {
-                CgosStorageAreaErase(
-                    self.handle,
-                    self.unit,
-                    offset.try_into().unwrap(),
-                    length.try_into().unwrap(),
-                )
-            },
-            0,
-        );
+    pub fn write(&self, offset: usize, data: &[u8]) -> Result<()> {
+        self.check_bounds(offset, data.len())?;
+        ffi_call!(CgosStorageAreaWrite(
+            self.handle,
+            self.unit,
+            offset.try_into().unwrap(),
+            data.as_ptr() as *mut _,
+            data.len().try_into().unwrap(),
+        ); unit = self.unit, offset, length = data.len())
+    }
+
+    pub fn read_all(&self) -> Result<Vec<u8>> {
+        let mut data = vec![0; self.size()];
+        self.read_chunked(0, &mut data)?;
+        Ok(data)
+    }
+
+    pub fn write_all(&self, data: &[u8]) -> Result<()> {
+        let size = self.size();
+        if data.len() != size {
+            return Err(CgosError::SizeMismatch {
+                expected: size,
+                actual: data.len(),
+            });
+        }
+        self.write_chunked(0, data)
+    }
+
+    /// Writes `data` and reads it back, failing if the area doesn't return what was written.
+    pub fn write_verified(&self, offset: usize, data: &[u8]) -> Result<()> {
+        self.write_chunked(offset, data)?;
+        let mut read_back = vec![0; data.len()];
+        self.read_chunked(offset, &mut read_back)?;
+        if read_back != data {
+            return Err(CgosError::VerificationFailed { offset });
+        }
+        Ok(())
     }
 
-    pub fn erase_status(&self, offset: usize, length: usize) -> EraseStatus {
+    /// Replaces the contents stored with [`Self::write_atomic`] without ever leaving a torn copy.
+    ///
+    /// The area is split into two slots written alternately, each holding a sequence number,
+    /// the data and a CRC-32. If power is lost mid-write, [`Self::read_atomic`] still returns the
+    /// previous contents.
+    pub fn write_atomic(&self, data: &[u8]) -> Result<()> {
+        let (slot, sequence) = match self.newest_slot()? {
+            Some((slot, sequence, _)) => (1 - slot, sequence.wrapping_add(1)),
+            None => (0, 0),
+        };
+        let mut frame = ATOMIC_MAGIC.to_vec();
+        frame.extend(sequence.to_le_bytes());
+        frame.extend(u32::try_from(data.len()).unwrap_or(u32::MAX).to_le_bytes());
+        frame.extend(data);
+        let checksum = crc32(&frame);
+        frame.extend(checksum.to_le_bytes());
+        if frame.len() > self.slot_size() {
+            return Err(CgosError::StorageFull);
+        }
+        self.write_verified(slot * self.slot_size(), &frame)
+    }
+
+    /// Returns the contents last stored with [`Self::write_atomic`], `None` if there are none.
+    pub fn read_atomic(&self) -> Result<Option<Vec<u8>>> {
+        Ok(self.newest_slot()?.map(|(_, _, data)| data))
+    }
+
+    fn slot_size(&self) -> usize {
+        self.size() / 2
+    }
+
+    fn newest_slot(&self) -> Result<Option<(usize, u32, Vec<u8>)>> {
+        let first = self.read_slot(0)?;
+        let second = self.read_slot(1)?;
+        Ok(match (first, second) {
+            (Some(first), Some(second)) => {
+                if (second.0.wrapping_sub(first.0) as i32) > 0 {
+                    Some((1, second.0, second.1))
+                } else {
+                    Some((0, first.0, first.1))
+                }
+            }
+            (Some(first), None) => Some((0, first.0, first.1)),
+            (None, Some(second)) => Some((1, second.0, second.1)),
+            (None, None) => None,
+        })
+    }
+
+    fn read_slot(&self, slot: usize) -> Result<Option<(u32, Vec<u8>)>> {
+        let mut frame = vec![0; self.slot_size()];
+        self.read_chunked(slot * self.slot_size(), &mut frame)?;
+        if frame.len() < ATOMIC_HEADER_LENGTH + 4 || !frame.starts_with(ATOMIC_MAGIC) {
+            return Ok(None);
+        }
+        let field =
+            |offset: usize| u32::from_le_bytes(frame[offset..offset + 4].try_into().unwrap());
+        let sequence = field(ATOMIC_MAGIC.len());
+        let length = field(ATOMIC_MAGIC.len() + 4) as usize;
+        let Some(end) = ATOMIC_HEADER_LENGTH
+            .checked_add(length)
+            .filter(|&end| end + 4 <= frame.len())
+        else {
+            return Ok(None);
+        };
+        if crc32(&frame[..end]) != field(end) {
+            return Ok(None);
+        }
+        Ok(Some((sequence, frame[ATOMIC_HEADER_LENGTH..end].to_vec())))
+    }
+
+    fn check_bounds(&self, offset: usize, length: usize) -> Result<()> {
+        let size = self.size();
+        if !matches!(offset.checked_add(length), Some(end) if end <= size) {
+            return Err(CgosError::OutOfBounds {
+                offset,
+                length,
+                size,
+            });
+        }
+        Ok(())
+    }
+
+    /// Like `read()`, split into transfers of at most one block each.
+    fn read_chunked(&self, offset: usize, data: &mut [u8]) -> Result<()> {
+        self.check_bounds(offset, data.len())?;
+        for chunk in self.chunks(offset, data.len()) {
+            self.read(
+                chunk.start,
+                &mut data[chunk.start - offset..chunk.end - offset],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Like `write()`, split into transfers of at most one block each.
+    pub(crate) fn write_chunked(&self, offset: usize, data: &[u8]) -> Result<()> {
+        self.check_bounds(offset, data.len())?;
+        for chunk in self.chunks(offset, data.len()) {
+            self.write(chunk.start, &data[chunk.start - offset..chunk.end - offset])?;
+        }
+        Ok(())
+    }
+
+    /// Splits `offset..offset + length` at block boundaries, the range has to be in bounds.
+    fn chunks(&self, offset: usize, length: usize) -> impl Iterator<Item = Range<usize>> {
+        let chunk_size = self.chunk_size();
+        let end = offset + length;
+        let mut start = offset;
+        from_fn(move || {
+            (start < end).then(|| {
+                let chunk = start..((start / chunk_size + 1) * chunk_size).min(end);
+                start = chunk.end;
+                chunk
+            })
+        })
+    }
+
+    /// Extends `offset..offset + length` to whole blocks, without going past the end of the area.
+    fn block_range(&self, offset: usize, length: usize) -> Range<usize> {
+        let block_size = self.chunk_size();
+        let start = offset / block_size * block_size;
+        let end = (offset + length + block_size - 1) / block_size * block_size;
+        start..end.min(self.size())
+    }
+
+    fn chunk_size(&self) -> usize {
+        match self.block_size() {
+            0 => self.size().max(1),
+            block_size => block_size,
+        }
+    }
+
+    pub fn erase(&self, offset: usize, length: usize) -> Result<()> {
+        self.check_bounds(offset, length)?;
+        ffi_call!(CgosStorageAreaErase(
+            self.handle,
+            self.unit,
+            offset.try_into().unwrap(),
+            length.try_into().unwrap(),
+        ); unit = self.unit, offset, length)
+    }
+
+    pub fn erase_status(&self, offset: usize, length: usize) -> Result<EraseStatus> {
         let mut status = 0;
-        assert_ne!(
-            unsafe {
-                CgosStorageAreaEraseStatus(
-                    self.handle,
-                    self.unit,
-                    offset.try_into().unwrap(),
-                    length.try_into().unwrap(),
-                    &mut status,
-                )
-            },
-            0,
-        );
-        status.into()
-    }
-
-    pub fn lock(&self, secret: &[u8]) {
-        assert_ne!(
-            unsafe {
-                CgosStorageAreaLock(
-                    self.handle,
-                    self.unit,
-                    0,
-                    secret.as_ptr() as *mut _,
-                    secret.len().try_into().unwrap(),
-                )
-            },
+        ffi_call!(CgosStorageAreaEraseStatus(
+            self.handle,
+            self.unit,
+            offset.try_into().unwrap(),
+            length.try_into().unwrap(),
+            &mut status,
+        ); unit = self.unit, offset, length)?;
+        Ok(status.into())
+    }
+
+    /// Starts erasing and polls `erase_status()` until the erase finished, failed or `timeout` passed.
+    ///
+    /// An unknown status is treated as a failed erase.
+    pub fn erase_and_wait(&self, offset: usize, length: usize, timeout: Duration) -> Result<()> {
+        const POLL_INTERVAL: Duration = Duration::from_millis(10);
+        let deadline = Instant::now() + timeout;
+        self.erase(offset, length)?;
+        loop {
+            match self.erase_status(offset, length)? {
+                EraseStatus::Successful => return Ok(()),
+                EraseStatus::Failed | EraseStatus::Unknown(_) => {
+                    return Err(CgosError::EraseFailed)
+                }
+                EraseStatus::InProgress if Instant::now() >= deadline => {
+                    return Err(CgosError::Timeout)
+                }
+                EraseStatus::InProgress => sleep(POLL_INTERVAL),
+            }
+        }
+    }
+
+    pub fn lock(&self, secret: &[u8]) -> Result<()> {
+        ffi_call!(CgosStorageAreaLock(
+            self.handle,
+            self.unit,
             0,
-        );
+            secret.as_ptr() as *mut _,
+            secret.len().try_into().unwrap(),
+        ); unit = self.unit)
     }
 
-    pub fn unlock(&self, secret: &[u8]) {
-        assert_ne!(
-            unsafe {
-                CgosStorageAreaUnlock(
-                    self.handle,
-                    self.unit,
-                    0,
-                    secret.as_ptr() as *mut _,
-                    secret.len().try_into().unwrap(),
-                )
-            },
+    pub fn unlock(&self, secret: &[u8]) -> Result<()> {
+        ffi_call!(CgosStorageAreaUnlock(
+            self.handle,
+            self.unit,
             0,
-        );
+            secret.as_ptr() as *mut _,
+            secret.len().try_into().unwrap(),
+        ); unit = self.unit)
     }
 
     pub fn is_locked(&self) -> bool {
-        unsafe { CgosStorageAreaIsLocked(self.handle, self.unit, 0) != 0 }
+        ffi_value!(CgosStorageAreaIsLocked(self.handle, self.unit, 0); unit = self.unit) != 0
+    }
+
+    /// Unlocks the area until the returned guard is dropped, which locks it again with `secret`.
+    pub fn unlocked(&self, secret: &[u8]) -> Result<UnlockedArea<'_, 'library>> {
+        self.unlock(secret)?;
+        Ok(UnlockedArea {
+            area: self,
+            secret: secret.to_vec(),
+        })
+    }
+}
+
+/// Cursor over a storage area implementing `Read`, `Write` and `Seek`.
+///
+/// Transfers are extended to whole blocks internally, partially written blocks are read back and
+/// patched first. Flash areas still have to be erased before they are written.
+pub struct StorageAreaFile<'area, 'library> {
+    area: &'area StorageArea<'library>,
+    position: usize,
+}
+
+impl<'area, 'library> StorageAreaFile<'area, 'library> {
+    pub fn new(area: &'area StorageArea<'library>) -> Self {
+        Self { area, position: 0 }
+    }
+
+    fn remaining(&self, length: usize) -> usize {
+        length.min(self.area.size().saturating_sub(self.position))
+    }
+}
+
+impl<'area, 'library> Read for StorageAreaFile<'area, 'library> {
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        let length = self.remaining(buffer.len());
+        if length == 0 {
+            return Ok(0);
+        }
+        let range = self.area.block_range(self.position, length);
+        let mut data = vec![0; range.len()];
+        self.area
+            .read_chunked(range.start, &mut data)
+            .map_err(|error| io::Error::new(io::ErrorKind::Other, error))?;
+        buffer[..length].copy_from_slice(&data[self.position - range.start..][..length]);
+        self.position += length;
+        Ok(length)
+    }
+}
+
+impl<'area, 'library> Write for StorageAreaFile<'area, 'library> {
+    fn write(&mut self, buffer: &[u8]) -> io::Result<usize> {
+        let length = self.remaining(buffer.len());
+        if length == 0 {
+            return Ok(0);
+        }
+        let range = self.area.block_range(self.position, length);
+        let mut data = vec![0; range.len()];
+        if range.len() != length {
+            self.area
+                .read_chunked(range.start, &mut data)
+                .map_err(|error| io::Error::new(io::ErrorKind::Other, error))?;
+        }
+        data[self.position - range.start..][..length].copy_from_slice(&buffer[..length]);
+        self.area
+            .write_chunked(range.start, &data)
+            .map_err(|error| io::Error::new(io::ErrorKind::Other, error))?;
+        self.position += length;
+        Ok(length)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'area, 'library> Seek for StorageAreaFile<'area, 'library> {
+    fn seek(&mut self, position: SeekFrom) -> io::Result<u64> {
+        let (base, offset) = match position {
+            SeekFrom::Start(offset) => (0, offset as i64),
+            SeekFrom::End(offset) => (self.area.size() as i64, offset),
+            SeekFrom::Current(offset) => (self.position as i64, offset),
+        };
+        let position = base
+            .checked_add(offset)
+            .filter(|&position| position >= 0)
+            .ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidInput, "seek before start of area")
+            })?;
+        self.position = position as usize;
+        Ok(self.position as u64)
+    }
+}
+
+pub struct UnlockedArea<'area, 'library> {
+    area: &'area StorageArea<'library>,
+    secret: Vec<u8>,
+}
+
+impl<'area, 'library> Deref for UnlockedArea<'area, 'library> {
+    type Target = StorageArea<'library>;
+
+    fn deref(&self) -> &Self::Target {
+        self.area
+    }
+}
+
+impl<'area, 'library> Drop for UnlockedArea<'area, 'library> {
+    fn drop(&mut self) {
+        let _ = self.area.lock(&self.secret);
     }
 }
 
@@ -155,10 +470,48 @@ bitflags! {
     }
 }
 
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum StorageKind {
+    Eeprom,
+    Flash,
+    Cmos,
+    Ram,
+    /// Type value not known to this crate, e.g. a vendor specific area.
+    Other(u32),
+}
+
+impl From<u32> for StorageKind {
+    fn from(value: u32) -> Self {
+        match value {
+            CGOS_STORAGE_AREA_EEPROM => StorageKind::Eeprom,
+            CGOS_STORAGE_AREA_FLASH => StorageKind::Flash,
+            CGOS_STORAGE_AREA_CMOS => StorageKind::Cmos,
+            CGOS_STORAGE_AREA_RAM => StorageKind::Ram,
+            _ => StorageKind::Other(value),
+        }
+    }
+}
+
+impl From<StorageKind> for u32 {
+    fn from(kind: StorageKind) -> Self {
+        match kind {
+            StorageKind::Eeprom => CGOS_STORAGE_AREA_EEPROM,
+            StorageKind::Flash => CGOS_STORAGE_AREA_FLASH,
+            StorageKind::Cmos => CGOS_STORAGE_AREA_CMOS,
+            StorageKind::Ram => CGOS_STORAGE_AREA_RAM,
+            StorageKind::Other(value) => value,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum EraseStatus {
     Successful,
     InProgress,
     Failed,
+    /// Status value not known to this crate.
+    Unknown(u32),
 }
 
 impl From<u32> for EraseStatus {
@@ -167,7 +520,73 @@ impl From<u32> for EraseStatus {
             0 => EraseStatus::Successful,
             1 => EraseStatus::InProgress,
             2 => EraseStatus::Failed,
-            _ => panic!("unexpected status {value}"),
+            _ => EraseStatus::Unknown(value),
         }
     }
 }
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+    use super::*;
+    use crate::{
+        board::BoardClass,
+        congatec::Congatec,
+        mock::{install, test_lock, with_board, MockBoard, MockStorageArea},
+    };
+
+    fn install_area(size: usize, block_size: usize) {
+        let mut area = MockStorageArea::new(StorageKind::Eeprom, size);
+        area.data = (0..size).map(|byte| byte as u8).collect();
+        area.block_size = block_size;
+        install(vec![MockBoard {
+            storage_areas: vec![area],
+            ..Default::default()
+        }]);
+    }
+
+    #[test]
+    fn read_all_reassembles_blocks() {
+        let _lock = test_lock();
+        install_area(100, 16);
+        let library = Congatec::new().unwrap();
+        let board = library.get_board(BoardClass::ALL, 0).unwrap();
+        let area = board.get_storage_area_from_index(0).unwrap();
+        let expected = with_board(0, |board| board.storage_areas[0].data.clone());
+        assert_eq!(area.read_all().unwrap(), expected);
+    }
+
+    #[test]
+    fn write_all_fills_every_block() {
+        let _lock = test_lock();
+        install_area(100, 16);
+        let library = Congatec::new().unwrap();
+        let board = library.get_board(BoardClass::ALL, 0).unwrap();
+        let area = board.get_storage_area_from_index(0).unwrap();
+        let data: Vec<_> = (0..100).map(|byte| 255 - byte as u8).collect();
+        area.write_all(&data).unwrap();
+        assert_eq!(
+            with_board(0, |board| board.storage_areas[0].data.clone()),
+            data
+        );
+        assert!(matches!(
+            area.write_all(&data[1..]),
+            Err(CgosError::SizeMismatch {
+                expected: 100,
+                actual: 99,
+            })
+        ));
+    }
+
+    #[test]
+    fn verified_and_atomic_writes_span_blocks() {
+        let _lock = test_lock();
+        install_area(100, 16);
+        let library = Congatec::new().unwrap();
+        let board = library.get_board(BoardClass::ALL, 0).unwrap();
+        let area = board.get_storage_area_from_index(0).unwrap();
+        area.write_verified(5, &[0xaa; 40]).unwrap();
+        area.write_atomic(b"first").unwrap();
+        area.write_atomic(&[0x55; 30]).unwrap();
+        assert_eq!(area.read_atomic().unwrap(), Some(vec![0x55; 30]));
+    }
+}
@@ -0,0 +1,90 @@
+use std::{
+    panic::resume_unwind,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use futures_util::{stream::unfold, Stream};
+use tokio::{task::spawn_blocking, time::interval};
+
+use crate::{
+    error::Result,
+    fan::Fan,
+    sensor::{Reading, Sensor},
+    status::Status,
+    temperature::{Celsius, Temperature},
+    voltage::{Millivolts, Voltage},
+};
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Timestamped<T> {
+    pub time: SystemTime,
+    pub value: T,
+    pub status: Status,
+}
+
+impl<'library> Temperature<'library> {
+    /// Samples the sensor every `period` on the blocking thread pool of the tokio runtime.
+    pub fn stream(
+        &self,
+        period: Duration,
+    ) -> impl Stream<Item = Result<Timestamped<Celsius>>> + 'library {
+        let temperature = Arc::new(self.detached());
+        poll(period, move || temperature.current())
+    }
+}
+
+impl<'library> Fan<'library> {
+    /// Samples the fan every `period` on the blocking thread pool of the tokio runtime.
+    pub fn stream(
+        &self,
+        period: Duration,
+    ) -> impl Stream<Item = Result<Timestamped<i32>>> + 'library {
+        let fan = Arc::new(self.detached());
+        poll(period, move || fan.current())
+    }
+}
+
+impl<'library> Voltage<'library> {
+    /// Samples the sensor every `period` on the blocking thread pool of the tokio runtime.
+    pub fn stream(
+        &self,
+        period: Duration,
+    ) -> impl Stream<Item = Result<Timestamped<Millivolts>>> + 'library {
+        let voltage = Arc::new(self.detached());
+        poll(period, move || voltage.current())
+    }
+}
+
+impl<'library> Sensor<'library> {
+    /// Samples the sensor every `period` on the blocking thread pool of the tokio runtime.
+    pub fn stream(
+        &self,
+        period: Duration,
+    ) -> impl Stream<Item = Result<Timestamped<Reading>>> + 'library {
+        let sensor = Arc::new(self.detached());
+        poll(period, move || sensor.current())
+    }
+}
+
+fn poll<T, Read>(period: Duration, read: Read) -> impl Stream<Item = Result<Timestamped<T>>>
+where
+    T: Send + 'static,
+    Read: Fn() -> Result<(T, Status)> + Clone + Send + 'static,
+{
+    unfold((None, read), move |(ticker, read)| async move {
+        let mut ticker = ticker.unwrap_or_else(|| interval(period));
+        ticker.tick().await;
+        let time = SystemTime::now();
+        let reader = read.clone();
+        let result = spawn_blocking(reader)
+            .await
+            .unwrap_or_else(|error| resume_unwind(error.into_panic()));
+        let reading = result.map(|(value, status)| Timestamped {
+            time,
+            value,
+            status,
+        });
+        Some((reading, (Some(ticker), read)))
+    })
+}
@@ -0,0 +1,166 @@
+use std::{
+    env::var,
+    io,
+    mem::{size_of, zeroed},
+    os::unix::{io::AsRawFd, net::UnixDatagram},
+    path::PathBuf,
+    process::id,
+    time::Duration,
+};
+
+use crate::{
+    error::{CgosError, Result},
+    watchdog::Watchdog,
+};
+
+impl<'library> Watchdog<'library> {
+    /// Arms the watchdog with the `WatchdogSec=` timeout systemd configured for this service.
+    ///
+    /// Each `SystemdBridge::notify()` then feeds both the hardware and the systemd watchdog.
+    pub fn bridge_systemd(&self) -> Result<SystemdBridge<'_, 'library>> {
+        let bridge = SystemdBridge::new(self)?;
+        self.set_timeout(bridge.timeout)?;
+        Ok(bridge)
+    }
+}
+
+pub struct SystemdBridge<'watchdog, 'library> {
+    watchdog: &'watchdog Watchdog<'library>,
+    socket: UnixDatagram,
+    address: NotifyAddress,
+    timeout: Duration,
+}
+
+/// `NOTIFY_SOCKET`, a leading `@` selects the abstract namespace.
+enum NotifyAddress {
+    Path(PathBuf),
+    Abstract(libc::sockaddr_un, libc::socklen_t),
+}
+
+impl<'watchdog, 'library> SystemdBridge<'watchdog, 'library> {
+    fn new(watchdog: &'watchdog Watchdog<'library>) -> Result<Self> {
+        if let Ok(pid) = var("WATCHDOG_PID") {
+            if pid.parse() != Ok(id()) {
+                return Err(CgosError::SystemdWatchdogNotEnabled);
+            }
+        }
+        let timeout = var("WATCHDOG_USEC")
+            .ok()
+            .and_then(|microseconds| microseconds.parse().ok())
+            .map(Duration::from_micros)
+            .ok_or(CgosError::SystemdWatchdogNotEnabled)?;
+        let path = var("NOTIFY_SOCKET").map_err(|_| CgosError::SystemdWatchdogNotEnabled)?;
+        let address = match path.strip_prefix('@') {
+            Some(name) => abstract_address(name.as_bytes())?,
+            None => NotifyAddress::Path(path.into()),
+        };
+        Ok(Self {
+            watchdog,
+            socket: UnixDatagram::unbound()?,
+            address,
+            timeout,
+        })
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// How often `notify()` should be called, half the timeout as recommended by systemd.
+    pub fn interval(&self) -> Duration {
+        self.timeout / 2
+    }
+
+    pub fn notify(&self) -> Result<()> {
+        self.watchdog.trigger()?;
+        self.send(b"WATCHDOG=1")?;
+        Ok(())
+    }
+
+    fn send(&self, message: &[u8]) -> io::Result<()> {
+        let (address, length) = match &self.address {
+            NotifyAddress::Path(path) => return self.socket.send_to(message, path).map(drop),
+            NotifyAddress::Abstract(address, length) => (address, *length),
+        };
+        let sent = unsafe {
+            libc::sendto(
+                self.socket.as_raw_fd(),
+                message.as_ptr().cast(),
+                message.len(),
+                0,
+                (address as *const libc::sockaddr_un).cast(),
+                length,
+            )
+        };
+        if sent < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+}
+
+/// Builds the address of an abstract socket, whose path is a NUL byte followed by `name`.
+fn abstract_address(name: &[u8]) -> io::Result<NotifyAddress> {
+    let mut address: libc::sockaddr_un = unsafe { zeroed() };
+    if name.len() >= address.sun_path.len() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "abstract socket name too long",
+        ));
+    }
+    address.sun_family = libc::AF_UNIX as libc::sa_family_t;
+    for (target, byte) in address.sun_path[1..].iter_mut().zip(name) {
+        *target = *byte as libc::c_char;
+    }
+    let length = size_of::<libc::sa_family_t>() + 1 + name.len();
+    Ok(NotifyAddress::Abstract(address, length as libc::socklen_t))
+}
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+    use std::{
+        env::{set_var, temp_dir},
+        fs::remove_file,
+    };
+
+    use super::*;
+    use crate::{
+        board::BoardClass,
+        congatec::Congatec,
+        mock::{install, test_lock, with_board, MockBoard, MockWatchdog},
+    };
+
+    #[test]
+    fn notify_feeds_both_watchdogs() {
+        let _lock = test_lock();
+        install(vec![MockBoard {
+            watchdogs: vec![MockWatchdog::default()],
+            ..Default::default()
+        }]);
+        let path = temp_dir().join(format!("cgos-notify-{}", id()));
+        let _ = remove_file(&path);
+        let systemd = UnixDatagram::bind(&path).unwrap();
+        set_var("NOTIFY_SOCKET", &path);
+        set_var("WATCHDOG_USEC", "4000000");
+        set_var("WATCHDOG_PID", id().to_string());
+        let library = Congatec::new().unwrap();
+        let board = library.get_board(BoardClass::ALL, 0).unwrap();
+        let watchdog = board.get_watchdog(0).unwrap();
+        let bridge = watchdog.bridge_systemd().unwrap();
+        assert_eq!(bridge.interval(), Duration::from_secs(2));
+        bridge.notify().unwrap();
+        let mut message = [0; 16];
+        let length = systemd.recv(&mut message).unwrap();
+        let _ = remove_file(&path);
+        assert_eq!(&message[..length], b"WATCHDOG=1");
+        with_board(0, |board| {
+            assert_eq!(board.watchdogs[0].config.timeout, Duration::from_secs(4));
+            assert_eq!(board.watchdogs[0].triggers, 1);
+        });
+        set_var("WATCHDOG_PID", "1");
+        assert!(matches!(
+            watchdog.bridge_systemd(),
+            Err(CgosError::SystemdWatchdogNotEnabled)
+        ));
+    }
+}
@@ -1,8 +1,15 @@
 use std::{
+    fmt::{self, Display, Formatter},
     marker::PhantomData,
     mem::{size_of, zeroed},
+    sync::mpsc::{channel, RecvTimeoutError, Sender},
+    thread::{spawn, JoinHandle},
+    time::Duration,
 };
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 use crate::{
     bindings::{
         CgosTemperatureCount, CgosTemperatureGetCurrent, CgosTemperatureGetInfo,
@@ -10,6 +17,7 @@ use crate::{
         CGOS_TEMP_BOTDIMM_ENV, CGOS_TEMP_BOX, CGOS_TEMP_CHIPSETS, CGOS_TEMP_CPU, CGOS_TEMP_ENV,
         CGOS_TEMP_OTHER, CGOS_TEMP_TOPDIMM_ENV, CGOS_TEMP_VIDEO,
     },
+    error::{ffi_call, ffi_value, CgosError, Result},
     status::Status,
 };
 
@@ -21,61 +29,211 @@ pub struct Temperature<'library> {
 
 impl<'library> Temperature<'library> {
     pub(crate) fn amount(handle: u32) -> usize {
-        unsafe { CgosTemperatureCount(handle) as usize }
+        ffi_value!(CgosTemperatureCount(handle)) as usize
     }
 
-    pub(crate) fn new(handle: u32, index: usize) -> Temperature<'library> {
-        Self {
+    pub(crate) fn new(handle: u32, index: usize) -> Result<Temperature<'library>> {
+        let amount = Self::amount(handle);
+        if index >= amount {
+            return Err(CgosError::IndexOutOfRange { index, amount });
+        }
+        Ok(Self {
             handle,
             index: index.try_into().unwrap(),
             _library_lifetime: PhantomData,
+        })
+    }
+
+    /// Board handle and sensor index to pass to CGOS functions directly.
+    pub fn raw_parts(&self) -> (u32, u32) {
+        (self.handle, self.index)
+    }
+
+    /// # Safety
+    ///
+    /// `handle` has to stay open for `'library` and `index` is not validated.
+    pub unsafe fn from_raw_parts(handle: u32, index: u32) -> Self {
+        Self {
+            handle,
+            index,
+            _library_lifetime: PhantomData,
+        }
+    }
+
+    /// Copy not tied to the library lifetime, for background threads whose owner is.
+    ///
+    /// The owner has to stop the thread when dropped, leaking it outlives the board handle.
+    pub(crate) fn detached(&self) -> Temperature<'static> {
+        Temperature {
+            handle: self.handle,
+            index: self.index,
+            _library_lifetime: PhantomData,
         }
     }
 
-    pub fn info(&self) -> TemperatureInfo {
+    pub fn info(&self) -> Result<TemperatureInfo> {
         let mut info: CGOSTEMPERATUREINFO = unsafe { zeroed() };
         info.dwSize = size_of::<CGOSTEMPERATUREINFO>() as u32;
-        assert_ne!(
-            unsafe { CgosTemperatureGetInfo(self.handle, self.index, &mut info) },
-            0
-        );
-        info.into()
+        ffi_call!(CgosTemperatureGetInfo(self.handle, self.index, &mut info))?;
+        Ok(info.into())
     }
 
-    pub fn current(&self) -> (f32, Status) {
+    pub fn current(&self) -> Result<(Celsius, Status)> {
         let mut value = 0;
         let mut flags = 0;
-        assert_ne!(
-            unsafe { CgosTemperatureGetCurrent(self.handle, self.index, &mut value, &mut flags) },
-            0,
-        );
-        (
-            value as i32 as f32 / 1000.0,
+        ffi_call!(CgosTemperatureGetCurrent(
+            self.handle,
+            self.index,
+            &mut value,
+            &mut flags
+        ))?;
+        Ok((
+            Celsius::from_millidegrees(value),
             Status::from_bits_truncate(flags),
-        )
+        ))
+    }
+
+    pub fn limits(&self) -> Result<(TemperatureThreshold, TemperatureThreshold)> {
+        let info = self.info()?;
+        Ok((
+            TemperatureThreshold {
+                alarm: info.alarm_low,
+                hysteresis: info.hysteresis_low,
+            },
+            TemperatureThreshold {
+                alarm: info.alarm_high,
+                hysteresis: info.hysteresis_high,
+            },
+        ))
     }
 
-    pub fn set_limits(&self, info: TemperatureInfo) {
+    /// Keeps all other fields of the sensor info as currently reported.
+    pub fn set_limits(&self, low: TemperatureThreshold, high: TemperatureThreshold) -> Result<()> {
+        let info = TemperatureInfo {
+            alarm_low: low.alarm,
+            hysteresis_low: low.hysteresis,
+            alarm_high: high.alarm,
+            hysteresis_high: high.hysteresis,
+            ..self.info()?
+        };
+        self.set_info(info)
+    }
+
+    pub fn set_info(&self, info: TemperatureInfo) -> Result<()> {
         let mut info = info.into();
-        assert_ne!(
-            unsafe { CgosTemperatureSetLimits(self.handle, self.index, &mut info) },
-            0,
-        );
+        ffi_call!(CgosTemperatureSetLimits(self.handle, self.index, &mut info))
+    }
+
+    /// Samples the sensor every `interval` and calls `callback` whenever the alarm flag changes.
+    ///
+    /// Failed samples are skipped. Polling stops when the returned monitor is dropped.
+    pub fn monitor<Callback>(
+        &self,
+        interval: Duration,
+        mut callback: Callback,
+    ) -> TemperatureMonitor<'library>
+    where
+        Callback: FnMut(Celsius, Status) + Send + 'static,
+    {
+        let temperature = self.detached();
+        let (stop, stopped) = channel::<()>();
+        let thread = spawn(move || {
+            let mut alarm = false;
+            while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+                let Ok((value, status)) = temperature.current() else {
+                    continue;
+                };
+                if status.contains(Status::ALARM) != alarm {
+                    alarm = !alarm;
+                    callback(value, status);
+                }
+            }
+        });
+        TemperatureMonitor {
+            stop: Some(stop),
+            thread: Some(thread),
+            _library_lifetime: PhantomData,
+        }
+    }
+}
+
+/// Stops polling when dropped.
+///
+/// Only dropping stops the thread, a monitor leaked with e.g. `mem::forget` keeps sampling once
+/// the board is closed, through a handle CGOS may have handed out again.
+pub struct TemperatureMonitor<'library> {
+    stop: Option<Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+    _library_lifetime: PhantomData<&'library ()>,
+}
+
+impl<'library> Drop for TemperatureMonitor<'library> {
+    fn drop(&mut self) {
+        drop(self.stop.take());
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
     }
 }
 
+/// Alarm level and the hysteresis before the alarm clears again.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TemperatureThreshold {
+    pub alarm: Celsius,
+    pub hysteresis: Celsius,
+}
+
+/// Temperature in degrees Celsius, CGOS reports them in units of 1/1000 degree.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, Default, PartialEq, PartialOrd)]
+pub struct Celsius(pub f64);
+
+impl Celsius {
+    pub(crate) fn from_millidegrees(value: u32) -> Self {
+        Self(value as i32 as f64 / 1000.0)
+    }
+
+    pub(crate) fn to_millidegrees(self) -> u32 {
+        (self.0 * 1000.0).round() as i32 as u32
+    }
+
+    pub fn from_fahrenheit(fahrenheit: f64) -> Self {
+        Self((fahrenheit - 32.0) / 1.8)
+    }
+
+    pub fn from_kelvin(kelvin: f64) -> Self {
+        Self(kelvin - 273.15)
+    }
+
+    pub fn fahrenheit(self) -> f64 {
+        self.0 * 1.8 + 32.0
+    }
+
+    pub fn kelvin(self) -> f64 {
+        self.0 + 273.15
+    }
+}
+
+impl Display for Celsius {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(formatter, "{:.1} °C", self.0)
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Copy, Clone, Debug)]
 pub struct TemperatureInfo {
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
     pub type_: TemperatureType,
     pub status: Status,
     pub alarm: u32,
-    pub resolution: f32,
-    pub minimum: f32,
-    pub maximum: f32,
-    pub alarm_high: f32,
-    pub hysteresis_high: f32,
-    pub alarm_low: f32,
-    pub hysteresis_low: f32,
+    pub resolution: Celsius,
+    pub minimum: Celsius,
+    pub maximum: Celsius,
+    pub alarm_high: Celsius,
+    pub hysteresis_high: Celsius,
+    pub alarm_low: Celsius,
+    pub hysteresis_low: Celsius,
 }
 
 impl From<CGOSTEMPERATUREINFO> for TemperatureInfo {
@@ -84,36 +242,47 @@ impl From<CGOSTEMPERATUREINFO> for TemperatureInfo {
             type_: info.dwType.into(),
             status: Status::from_bits_truncate(info.dwFlags),
             alarm: info.dwAlarm,
-            resolution: info.dwRes as i32 as f32 / 1000.0,
-            minimum: info.dwMin as i32 as f32 / 1000.0,
-            maximum: info.dwMax as i32 as f32 / 1000.0,
-            alarm_high: info.dwAlarmHi as i32 as f32 / 1000.0,
-            hysteresis_high: info.dwHystHi as i32 as f32 / 1000.0,
-            alarm_low: info.dwAlarmLo as i32 as f32 / 1000.0,
-            hysteresis_low: info.dwHystLo as i32 as f32 / 1000.0,
+            resolution: Celsius::from_millidegrees(info.dwRes),
+            minimum: Celsius::from_millidegrees(info.dwMin),
+            maximum: Celsius::from_millidegrees(info.dwMax),
+            alarm_high: Celsius::from_millidegrees(info.dwAlarmHi),
+            hysteresis_high: Celsius::from_millidegrees(info.dwHystHi),
+            alarm_low: Celsius::from_millidegrees(info.dwAlarmLo),
+            hysteresis_low: Celsius::from_millidegrees(info.dwHystLo),
         }
     }
 }
 
-impl Into<CGOSTEMPERATUREINFO> for TemperatureInfo {
-    fn into(self) -> CGOSTEMPERATUREINFO {
+impl From<TemperatureInfo> for CGOSTEMPERATUREINFO {
+    fn from(info: TemperatureInfo) -> Self {
         CGOSTEMPERATUREINFO {
             dwSize: size_of::<CGOSTEMPERATUREINFO>() as u32,
-            dwType: self.type_.into(),
-            dwFlags: self.status.bits(),
-            dwAlarm: self.alarm,
-            dwRes: (self.resolution * 1000.0) as i32 as u32,
-            dwMin: (self.minimum * 1000.0) as i32 as u32,
-            dwMax: (self.maximum * 1000.0) as i32 as u32,
-            dwAlarmHi: (self.alarm_high * 1000.0) as i32 as u32,
-            dwHystHi: (self.hysteresis_high * 1000.0) as i32 as u32,
-            dwAlarmLo: (self.alarm_low * 1000.0) as i32 as u32,
-            dwHystLo: (self.hysteresis_low * 1000.0) as i32 as u32,
+            dwType: info.type_.into(),
+            dwFlags: info.status.bits(),
+            dwAlarm: info.alarm,
+            dwRes: info.resolution.to_millidegrees(),
+            dwMin: info.minimum.to_millidegrees(),
+            dwMax: info.maximum.to_millidegrees(),
+            dwAlarmHi: info.alarm_high.to_millidegrees(),
+            dwHystHi: info.hysteresis_high.to_millidegrees(),
+            dwAlarmLo: info.alarm_low.to_millidegrees(),
+            dwHystLo: info.hysteresis_low.to_millidegrees(),
         }
     }
 }
 
-#[derive(Copy, Clone, Debug)]
+impl Display for TemperatureInfo {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "{} temperature, {} to {} in steps of {}, {}",
+            self.type_, self.minimum, self.maximum, self.resolution, self.status
+        )
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum TemperatureType {
     Cpu,
     Box,
@@ -125,11 +294,13 @@ pub enum TemperatureType {
     TopRAMEnvironment,
     BottomRAMEnvironment,
     Other,
+    /// Type value not known to this crate.
+    Unknown(u32),
 }
 
-impl Into<u32> for TemperatureType {
-    fn into(self) -> u32 {
-        match self {
+impl From<TemperatureType> for u32 {
+    fn from(type_: TemperatureType) -> Self {
+        match type_ {
             TemperatureType::Cpu => CGOS_TEMP_CPU,
             TemperatureType::Box => CGOS_TEMP_BOX,
             TemperatureType::Environment => CGOS_TEMP_ENV,
@@ -140,6 +311,7 @@ impl Into<u32> for TemperatureType {
             TemperatureType::Other => CGOS_TEMP_OTHER,
             TemperatureType::TopRAMEnvironment => CGOS_TEMP_TOPDIMM_ENV,
             TemperatureType::BottomRAMEnvironment => CGOS_TEMP_BOTDIMM_ENV,
+            TemperatureType::Unknown(value) => value,
         }
     }
 }
@@ -157,7 +329,66 @@ impl From<u32> for TemperatureType {
             CGOS_TEMP_OTHER => TemperatureType::Other,
             CGOS_TEMP_TOPDIMM_ENV => TemperatureType::TopRAMEnvironment,
             CGOS_TEMP_BOTDIMM_ENV => TemperatureType::BottomRAMEnvironment,
-            _ => panic!("unexpected type {value}"),
+            _ => TemperatureType::Unknown(value),
+        }
+    }
+}
+
+impl Display for TemperatureType {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            TemperatureType::Cpu => write!(formatter, "CPU"),
+            TemperatureType::Box => write!(formatter, "box"),
+            TemperatureType::Environment => write!(formatter, "environment"),
+            TemperatureType::Board => write!(formatter, "board"),
+            TemperatureType::Backplane => write!(formatter, "backplane"),
+            TemperatureType::Chipsets => write!(formatter, "chipsets"),
+            TemperatureType::Video => write!(formatter, "video"),
+            TemperatureType::TopRAMEnvironment => write!(formatter, "top RAM environment"),
+            TemperatureType::BottomRAMEnvironment => write!(formatter, "bottom RAM environment"),
+            TemperatureType::Other => write!(formatter, "other"),
+            TemperatureType::Unknown(value) => write!(formatter, "unknown type {value:#x}"),
         }
     }
 }
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+    use std::{sync::mpsc::channel, thread::sleep};
+
+    use super::*;
+    use crate::{
+        board::BoardClass,
+        congatec::Congatec,
+        mock::{install, test_lock, MockBoard, MockSensor, MockStep},
+    };
+
+    #[test]
+    fn monitor_reports_only_alarm_transitions() {
+        let _lock = test_lock();
+        let step = |milliseconds, value| MockStep {
+            time: Duration::from_millis(milliseconds),
+            value,
+        };
+        install(vec![MockBoard {
+            temperatures: vec![MockSensor {
+                value: 50_000,
+                script: vec![step(50, 90_000), step(70, 95_000), step(150, 50_000)],
+                alarm_high: 80_000,
+                ..Default::default()
+            }],
+            ..Default::default()
+        }]);
+        let library = Congatec::new().unwrap();
+        let board = library.get_board(BoardClass::ALL, 0).unwrap();
+        let temperature = board.get_temperature(0).unwrap();
+        let (sender, receiver) = channel();
+        let monitor = temperature.monitor(Duration::from_millis(5), move |celsius, status| {
+            let _ = sender.send((celsius, status.contains(Status::ALARM)));
+        });
+        sleep(Duration::from_millis(250));
+        drop(monitor);
+        let transitions: Vec<_> = receiver.iter().map(|(_, alarm)| alarm).collect();
+        assert_eq!(transitions, [true, false]);
+    }
+}
@@ -0,0 +1,352 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    marker::PhantomData,
+    mem::{size_of, zeroed},
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bindings::{
+        CgosVoltageCount, CgosVoltageGetCurrent, CgosVoltageGetInfo, CgosVoltageSetLimits,
+        CGOSVOLTAGEINFO, CGOS_VOLTAGE_12V_S0, CGOS_VOLTAGE_33V_S0, CGOS_VOLTAGE_33V_S5,
+        CGOS_VOLTAGE_5V_S0, CGOS_VOLTAGE_5V_S5, CGOS_VOLTAGE_AC, CGOS_VOLTAGE_BAT_CMOS,
+        CGOS_VOLTAGE_BAT_POWER, CGOS_VOLTAGE_CPU_CORE, CGOS_VOLTAGE_CPU_IO, CGOS_VOLTAGE_DC,
+        CGOS_VOLTAGE_DC_STANDBY, CGOS_VOLTAGE_OTHER, CGOS_VOLTAGE_VCOREA, CGOS_VOLTAGE_VCOREB,
+    },
+    error::{ffi_call, ffi_value, CgosError, Result},
+    status::Status,
+};
+
+pub struct Voltage<'library> {
+    handle: u32,
+    index: u32,
+    _library_lifetime: PhantomData<&'library ()>,
+}
+
+impl<'library> Voltage<'library> {
+    pub(crate) fn amount(handle: u32) -> usize {
+        ffi_value!(CgosVoltageCount(handle)) as usize
+    }
+
+    pub(crate) fn new(handle: u32, index: usize) -> Result<Voltage<'library>> {
+        let amount = Self::amount(handle);
+        if index >= amount {
+            return Err(CgosError::IndexOutOfRange { index, amount });
+        }
+        Ok(Self {
+            handle,
+            index: index.try_into().unwrap(),
+            _library_lifetime: PhantomData,
+        })
+    }
+
+    /// Board handle and sensor index to pass to CGOS functions directly.
+    pub fn raw_parts(&self) -> (u32, u32) {
+        (self.handle, self.index)
+    }
+
+    /// # Safety
+    ///
+    /// `handle` has to stay open for `'library` and `index` is not validated.
+    pub unsafe fn from_raw_parts(handle: u32, index: u32) -> Self {
+        Self {
+            handle,
+            index,
+            _library_lifetime: PhantomData,
+        }
+    }
+
+    /// Copy not tied to the library lifetime, for background threads whose owner is.
+    ///
+    /// The owner has to stop the thread when dropped, leaking it outlives the board handle.
+    pub(crate) fn detached(&self) -> Voltage<'static> {
+        Voltage {
+            handle: self.handle,
+            index: self.index,
+            _library_lifetime: PhantomData,
+        }
+    }
+
+    pub fn info(&self) -> Result<VoltageInfo> {
+        let mut info: CGOSVOLTAGEINFO = unsafe { zeroed() };
+        info.dwSize = size_of::<CGOSVOLTAGEINFO>() as u32;
+        ffi_call!(CgosVoltageGetInfo(self.handle, self.index, &mut info))?;
+        Ok(info.into())
+    }
+
+    pub fn current(&self) -> Result<(Millivolts, Status)> {
+        let mut value = 0;
+        let mut flags = 0;
+        ffi_call!(CgosVoltageGetCurrent(
+            self.handle,
+            self.index,
+            &mut value,
+            &mut flags
+        ))?;
+        Ok((Millivolts(value), Status::from_bits_truncate(flags)))
+    }
+
+    pub fn limits(&self) -> Result<(VoltageThreshold, VoltageThreshold)> {
+        let info = self.info()?;
+        Ok((
+            VoltageThreshold {
+                alarm: info.alarm_low,
+                hysteresis: info.hysteresis_low,
+            },
+            VoltageThreshold {
+                alarm: info.alarm_high,
+                hysteresis: info.hysteresis_high,
+            },
+        ))
+    }
+
+    /// Keeps all other fields of the sensor info as currently reported.
+    pub fn set_limits(&self, low: VoltageThreshold, high: VoltageThreshold) -> Result<()> {
+        let info = VoltageInfo {
+            alarm_low: low.alarm,
+            hysteresis_low: low.hysteresis,
+            alarm_high: high.alarm,
+            hysteresis_high: high.hysteresis,
+            ..self.info()?
+        };
+        self.set_info(info)
+    }
+
+    pub fn rail(&self) -> Result<Rail> {
+        Ok(self.info()?.type_.rail())
+    }
+
+    pub fn set_info(&self, info: VoltageInfo) -> Result<()> {
+        let mut info = info.into();
+        ffi_call!(CgosVoltageSetLimits(self.handle, self.index, &mut info))
+    }
+}
+
+/// Voltage as reported by CGOS, which uses millivolts for readings and limits.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Millivolts(pub u32);
+
+impl Millivolts {
+    pub fn volts(self) -> Volts {
+        self.into()
+    }
+}
+
+impl From<Millivolts> for Volts {
+    fn from(millivolts: Millivolts) -> Self {
+        Self(millivolts.0 as f64 / 1000.0)
+    }
+}
+
+impl Display for Millivolts {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(formatter, "{} mV", self.0)
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, Default, PartialEq, PartialOrd)]
+pub struct Volts(pub f64);
+
+impl From<Volts> for Millivolts {
+    fn from(volts: Volts) -> Self {
+        Self((volts.0 * 1000.0).round() as u32)
+    }
+}
+
+impl Display for Volts {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(formatter, "{:.3} V", self.0)
+    }
+}
+
+/// Supply rail a voltage sensor belongs to, independent of the sleep state it is measured in.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Rail {
+    V12,
+    V5,
+    V3_3,
+    Vcore,
+    VBat,
+    Other,
+}
+
+/// Alarm level and the hysteresis before the alarm clears again.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct VoltageThreshold {
+    pub alarm: Millivolts,
+    pub hysteresis: Millivolts,
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug)]
+pub struct VoltageInfo {
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
+    pub type_: VoltageType,
+    pub nominal: Millivolts,
+    pub status: Status,
+    pub alarm: u32,
+    pub resolution: Millivolts,
+    pub minimum: Millivolts,
+    pub maximum: Millivolts,
+    pub alarm_high: Millivolts,
+    pub hysteresis_high: Millivolts,
+    pub alarm_low: Millivolts,
+    pub hysteresis_low: Millivolts,
+}
+
+impl From<CGOSVOLTAGEINFO> for VoltageInfo {
+    fn from(info: CGOSVOLTAGEINFO) -> Self {
+        Self {
+            type_: info.dwType.into(),
+            nominal: Millivolts(info.dwNom),
+            status: Status::from_bits_truncate(info.dwFlags),
+            alarm: info.dwAlarm,
+            resolution: Millivolts(info.dwRes),
+            minimum: Millivolts(info.dwMin),
+            maximum: Millivolts(info.dwMax),
+            alarm_high: Millivolts(info.dwAlarmHi),
+            hysteresis_high: Millivolts(info.dwHystHi),
+            alarm_low: Millivolts(info.dwAlarmLo),
+            hysteresis_low: Millivolts(info.dwHystLo),
+        }
+    }
+}
+
+impl From<VoltageInfo> for CGOSVOLTAGEINFO {
+    fn from(info: VoltageInfo) -> Self {
+        CGOSVOLTAGEINFO {
+            dwSize: size_of::<CGOSVOLTAGEINFO>() as u32,
+            dwType: info.type_.into(),
+            dwNom: info.nominal.0,
+            dwFlags: info.status.bits(),
+            dwAlarm: info.alarm,
+            dwRes: info.resolution.0,
+            dwMin: info.minimum.0,
+            dwMax: info.maximum.0,
+            dwAlarmHi: info.alarm_high.0,
+            dwHystHi: info.hysteresis_high.0,
+            dwAlarmLo: info.alarm_low.0,
+            dwHystLo: info.hysteresis_low.0,
+        }
+    }
+}
+
+impl Display for VoltageInfo {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "{} voltage, nominal {}, {} to {}, {}",
+            self.type_, self.nominal, self.minimum, self.maximum, self.status
+        )
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum VoltageType {
+    CpuCore,
+    CpuIo,
+    Dc,
+    DcStandby,
+    CmosBattery,
+    PowerBattery,
+    Ac,
+    Other,
+    FiveVoltS0,
+    FiveVoltS5,
+    ThreePointThreeVoltS0,
+    ThreePointThreeVoltS5,
+    CoreA,
+    CoreB,
+    TwelveVoltS0,
+    /// Type value not known to this crate.
+    Unknown(u32),
+}
+
+impl VoltageType {
+    pub fn rail(self) -> Rail {
+        match self {
+            VoltageType::TwelveVoltS0 => Rail::V12,
+            VoltageType::FiveVoltS0 | VoltageType::FiveVoltS5 => Rail::V5,
+            VoltageType::ThreePointThreeVoltS0 | VoltageType::ThreePointThreeVoltS5 => Rail::V3_3,
+            VoltageType::CpuCore | VoltageType::CoreA | VoltageType::CoreB => Rail::Vcore,
+            VoltageType::CmosBattery | VoltageType::PowerBattery => Rail::VBat,
+            _ => Rail::Other,
+        }
+    }
+}
+
+impl From<VoltageType> for u32 {
+    fn from(type_: VoltageType) -> Self {
+        match type_ {
+            VoltageType::CpuCore => CGOS_VOLTAGE_CPU_CORE,
+            VoltageType::CpuIo => CGOS_VOLTAGE_CPU_IO,
+            VoltageType::Dc => CGOS_VOLTAGE_DC,
+            VoltageType::DcStandby => CGOS_VOLTAGE_DC_STANDBY,
+            VoltageType::CmosBattery => CGOS_VOLTAGE_BAT_CMOS,
+            VoltageType::PowerBattery => CGOS_VOLTAGE_BAT_POWER,
+            VoltageType::Ac => CGOS_VOLTAGE_AC,
+            VoltageType::Other => CGOS_VOLTAGE_OTHER,
+            VoltageType::FiveVoltS0 => CGOS_VOLTAGE_5V_S0,
+            VoltageType::FiveVoltS5 => CGOS_VOLTAGE_5V_S5,
+            VoltageType::ThreePointThreeVoltS0 => CGOS_VOLTAGE_33V_S0,
+            VoltageType::ThreePointThreeVoltS5 => CGOS_VOLTAGE_33V_S5,
+            VoltageType::CoreA => CGOS_VOLTAGE_VCOREA,
+            VoltageType::CoreB => CGOS_VOLTAGE_VCOREB,
+            VoltageType::TwelveVoltS0 => CGOS_VOLTAGE_12V_S0,
+            VoltageType::Unknown(value) => value,
+        }
+    }
+}
+
+impl From<u32> for VoltageType {
+    fn from(value: u32) -> Self {
+        match value {
+            CGOS_VOLTAGE_CPU_CORE => VoltageType::CpuCore,
+            CGOS_VOLTAGE_CPU_IO => VoltageType::CpuIo,
+            CGOS_VOLTAGE_DC => VoltageType::Dc,
+            CGOS_VOLTAGE_DC_STANDBY => VoltageType::DcStandby,
+            CGOS_VOLTAGE_BAT_CMOS => VoltageType::CmosBattery,
+            CGOS_VOLTAGE_BAT_POWER => VoltageType::PowerBattery,
+            CGOS_VOLTAGE_AC => VoltageType::Ac,
+            CGOS_VOLTAGE_OTHER => VoltageType::Other,
+            CGOS_VOLTAGE_5V_S0 => VoltageType::FiveVoltS0,
+            CGOS_VOLTAGE_5V_S5 => VoltageType::FiveVoltS5,
+            CGOS_VOLTAGE_33V_S0 => VoltageType::ThreePointThreeVoltS0,
+            CGOS_VOLTAGE_33V_S5 => VoltageType::ThreePointThreeVoltS5,
+            CGOS_VOLTAGE_VCOREA => VoltageType::CoreA,
+            CGOS_VOLTAGE_VCOREB => VoltageType::CoreB,
+            CGOS_VOLTAGE_12V_S0 => VoltageType::TwelveVoltS0,
+            _ => VoltageType::Unknown(value),
+        }
+    }
+}
+
+impl Display for VoltageType {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        let name = match self {
+            VoltageType::CpuCore => "CPU core",
+            VoltageType::CpuIo => "CPU IO",
+            VoltageType::Dc => "DC",
+            VoltageType::DcStandby => "DC standby",
+            VoltageType::CmosBattery => "CMOS battery",
+            VoltageType::PowerBattery => "power battery",
+            VoltageType::Ac => "AC",
+            VoltageType::Other => "other",
+            VoltageType::FiveVoltS0 => "5 V S0",
+            VoltageType::FiveVoltS5 => "5 V S5",
+            VoltageType::ThreePointThreeVoltS0 => "3.3 V S0",
+            VoltageType::ThreePointThreeVoltS5 => "3.3 V S5",
+            VoltageType::CoreA => "core A",
+            VoltageType::CoreB => "core B",
+            VoltageType::TwelveVoltS0 => "12 V S0",
+            VoltageType::Unknown(value) => return write!(formatter, "unknown type {value:#x}"),
+        };
+        write!(formatter, "{name}")
+    }
+}
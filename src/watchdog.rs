@@ -0,0 +1,699 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    marker::PhantomData,
+    mem::{forget, size_of, zeroed},
+    panic::{set_hook, take_hook},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{channel, RecvTimeoutError, Sender},
+        Arc,
+    },
+    thread::{spawn, JoinHandle},
+    time::Duration,
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bindings::{
+        CgosWDogCount, CgosWDogDisable, CgosWDogGetConfigStruct, CgosWDogSetConfig,
+        CgosWDogSetConfigStruct, CgosWDogTrigger, CGOSWDCONFIG, CGOSWDSTAGE, CGOS_WDOG_EVENT_BTN,
+        CGOS_WDOG_EVENT_INT, CGOS_WDOG_EVENT_RST, CGOS_WDOG_EVENT_SCI, CGOS_WDOG_EXPIRE_STAGES,
+        CGOS_WDOG_MODE_REBOOT_PC, CGOS_WDOG_MODE_RESTART_OS, CGOS_WDOG_MODE_STAGED,
+        CGOS_WDOG_OPMODE_DISABLED, CGOS_WDOG_OPMODE_EVENT_REPEAT, CGOS_WDOG_OPMODE_ONETIME_TRIG,
+        CGOS_WDOG_OPMODE_SINGLE_EVENT,
+    },
+    error::{ffi_call, ffi_value, CgosError, Result},
+};
+
+pub struct Watchdog<'library> {
+    handle: u32,
+    index: u32,
+    _library_lifetime: PhantomData<&'library ()>,
+}
+
+impl<'library> Watchdog<'library> {
+    pub(crate) fn amount(handle: u32) -> usize {
+        ffi_value!(CgosWDogCount(handle)) as usize
+    }
+
+    pub(crate) fn new(handle: u32, index: usize) -> Result<Watchdog<'library>> {
+        let amount = Self::amount(handle);
+        if index >= amount {
+            return Err(CgosError::IndexOutOfRange { index, amount });
+        }
+        Ok(Self {
+            handle,
+            index: index.try_into().unwrap(),
+            _library_lifetime: PhantomData,
+        })
+    }
+
+    /// Board handle and watchdog index to pass to CGOS functions directly.
+    pub fn raw_parts(&self) -> (u32, u32) {
+        (self.handle, self.index)
+    }
+
+    /// # Safety
+    ///
+    /// `handle` has to stay open for `'library` and `index` is not validated.
+    pub unsafe fn from_raw_parts(handle: u32, index: u32) -> Self {
+        Self {
+            handle,
+            index,
+            _library_lifetime: PhantomData,
+        }
+    }
+
+    pub fn trigger(&self) -> Result<()> {
+        ffi_call!(CgosWDogTrigger(self.handle, self.index))
+    }
+
+    /// Arms the watchdog to reboot the board unless triggered within `timeout`.
+    pub fn set_timeout(&self, timeout: Duration) -> Result<()> {
+        ffi_call!(CgosWDogSetConfig(
+            self.handle,
+            self.index,
+            milliseconds(timeout)?,
+            0,
+            CGOS_WDOG_MODE_REBOOT_PC,
+        ))
+    }
+
+    pub fn disable(&self) -> Result<()> {
+        ffi_call!(CgosWDogDisable(self.handle, self.index))
+    }
+
+    /// Like `set_timeout()`, but disables the watchdog again when the returned guard is dropped.
+    pub fn arm(&self, timeout: Duration) -> Result<WatchdogGuard<'_, 'library>> {
+        self.set_timeout(timeout)?;
+        Ok(WatchdogGuard { watchdog: self })
+    }
+
+    pub fn config(&self) -> Result<WatchdogConfig> {
+        let mut config: CGOSWDCONFIG = unsafe { zeroed() };
+        config.dwSize = size_of::<CGOSWDCONFIG>() as u32;
+        ffi_call!(CgosWDogGetConfigStruct(
+            self.handle,
+            self.index,
+            &mut config
+        ))?;
+        Ok(config.into())
+    }
+
+    pub fn set_config(&self, config: &WatchdogConfig) -> Result<()> {
+        let mut config = config.try_into()?;
+        ffi_call!(CgosWDogSetConfigStruct(
+            self.handle,
+            self.index,
+            &mut config
+        ))
+    }
+
+    /// Triggers the watchdog every `interval` from a background thread.
+    ///
+    /// Kicking stops for good once `health_check` returns `false`, so the watchdog fires as
+    /// configured. Dropping the returned keeper also stops kicking but leaves the watchdog armed.
+    pub fn keep_alive<HealthCheck>(
+        &self,
+        interval: Duration,
+        mut health_check: HealthCheck,
+    ) -> WatchdogKeeper<'library>
+    where
+        HealthCheck: FnMut() -> bool + Send + 'static,
+    {
+        let (handle, index) = (self.handle, self.index);
+        let healthy = Arc::new(AtomicBool::new(true));
+        let (stop, stopped) = channel::<()>();
+        let thread = spawn({
+            let healthy = healthy.clone();
+            move || {
+                let watchdog = Watchdog {
+                    handle,
+                    index,
+                    _library_lifetime: PhantomData,
+                };
+                while healthy.load(Ordering::SeqCst) && health_check() {
+                    let _ = watchdog.trigger();
+                    if !matches!(
+                        stopped.recv_timeout(interval),
+                        Err(RecvTimeoutError::Timeout)
+                    ) {
+                        break;
+                    }
+                }
+            }
+        });
+        WatchdogKeeper {
+            healthy,
+            stop: Some(stop),
+            thread: Some(thread),
+            _library_lifetime: PhantomData,
+        }
+    }
+}
+
+pub struct WatchdogGuard<'watchdog, 'library> {
+    watchdog: &'watchdog Watchdog<'library>,
+}
+
+impl<'watchdog, 'library> WatchdogGuard<'watchdog, 'library> {
+    pub fn trigger(&self) -> Result<()> {
+        self.watchdog.trigger()
+    }
+
+    /// Releases the guard without disabling the watchdog, leaving it armed.
+    pub fn forget(self) {
+        forget(self);
+    }
+}
+
+impl<'watchdog, 'library> Drop for WatchdogGuard<'watchdog, 'library> {
+    fn drop(&mut self) {
+        let _ = self.watchdog.disable();
+    }
+}
+
+/// Stops kicking when dropped.
+///
+/// A keeper leaked with e.g. `mem::forget` never stops its thread, which then keeps triggering
+/// through the handle of a closed board and may kick a watchdog of whichever board reuses it.
+pub struct WatchdogKeeper<'library> {
+    healthy: Arc<AtomicBool>,
+    stop: Option<Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+    _library_lifetime: PhantomData<&'library ()>,
+}
+
+impl<'library> WatchdogKeeper<'library> {
+    /// Installs a panic hook that stops kicking as soon as any thread panics.
+    ///
+    /// The previously installed hook is still called afterwards.
+    pub fn stop_on_panic(&self) {
+        let healthy = self.healthy.clone();
+        let previous_hook = take_hook();
+        set_hook(Box::new(move |info| {
+            healthy.store(false, Ordering::SeqCst);
+            previous_hook(info);
+        }));
+    }
+}
+
+impl<'library> Drop for WatchdogKeeper<'library> {
+    fn drop(&mut self) {
+        drop(self.stop.take());
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug)]
+pub struct WatchdogConfig {
+    pub timeout: Duration,
+    pub delay: Duration,
+    pub mode: WatchdogMode,
+    pub operating_mode: WatchdogOperatingMode,
+    pub stages: Vec<WatchdogStage>,
+}
+
+impl WatchdogConfig {
+    pub fn builder() -> WatchdogConfigBuilder {
+        WatchdogConfigBuilder::default()
+    }
+}
+
+impl From<CGOSWDCONFIG> for WatchdogConfig {
+    fn from(config: CGOSWDCONFIG) -> Self {
+        let stage_count = (config.dwStageCount as usize).min(config.stStages.len());
+        Self {
+            timeout: Duration::from_millis(config.dwTimeout.into()),
+            delay: Duration::from_millis(config.dwDelay.into()),
+            mode: config.dwMode.into(),
+            operating_mode: config.dwOpMode.into(),
+            stages: config.stStages[..stage_count]
+                .iter()
+                .map(|&stage| stage.into())
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<&WatchdogConfig> for CGOSWDCONFIG {
+    type Error = CgosError;
+
+    fn try_from(config: &WatchdogConfig) -> Result<Self> {
+        let mut raw: CGOSWDCONFIG = unsafe { zeroed() };
+        if config.stages.len() > raw.stStages.len() {
+            return Err(CgosError::TooManyStages {
+                amount: config.stages.len(),
+                maximum: raw.stStages.len(),
+            });
+        }
+        raw.dwSize = size_of::<CGOSWDCONFIG>() as u32;
+        raw.dwTimeout = milliseconds(config.timeout)?;
+        raw.dwDelay = milliseconds(config.delay)?;
+        raw.dwMode = config.mode.into();
+        raw.dwOpMode = config.operating_mode.into();
+        raw.dwStageCount = config.stages.len() as u32;
+        for (raw_stage, &stage) in raw.stStages.iter_mut().zip(&config.stages) {
+            *raw_stage = stage.try_into()?;
+        }
+        Ok(raw)
+    }
+}
+
+impl Display for WatchdogConfig {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "{:?} {:?}, timeout {:?}, delay {:?}, {} stages",
+            self.operating_mode,
+            self.mode,
+            self.timeout,
+            self.delay,
+            self.stages.len()
+        )
+    }
+}
+
+/// Builds a staged watchdog configuration, one `stage()` call per expiry stage.
+#[derive(Clone, Debug)]
+pub struct WatchdogConfigBuilder {
+    delay: Duration,
+    operating_mode: WatchdogOperatingMode,
+    stages: Vec<WatchdogStage>,
+}
+
+impl WatchdogConfigBuilder {
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    pub fn operating_mode(mut self, operating_mode: WatchdogOperatingMode) -> Self {
+        self.operating_mode = operating_mode;
+        self
+    }
+
+    pub fn stage(mut self, timeout: Duration, event: WatchdogEvent) -> Self {
+        self.stages.push(WatchdogStage { timeout, event });
+        self
+    }
+
+    pub fn build(self) -> Result<WatchdogConfig> {
+        let maximum = CGOS_WDOG_EXPIRE_STAGES as usize;
+        if self.stages.len() > maximum {
+            return Err(CgosError::TooManyStages {
+                amount: self.stages.len(),
+                maximum,
+            });
+        }
+        Ok(WatchdogConfig {
+            timeout: self
+                .stages
+                .first()
+                .map(|stage| stage.timeout)
+                .unwrap_or_default(),
+            delay: self.delay,
+            mode: WatchdogMode::Staged,
+            operating_mode: self.operating_mode,
+            stages: self.stages,
+        })
+    }
+}
+
+impl Default for WatchdogConfigBuilder {
+    fn default() -> Self {
+        Self {
+            delay: Duration::ZERO,
+            operating_mode: WatchdogOperatingMode::SingleEvent,
+            stages: Vec::new(),
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug)]
+pub struct WatchdogStage {
+    pub timeout: Duration,
+    pub event: WatchdogEvent,
+}
+
+impl From<CGOSWDSTAGE> for WatchdogStage {
+    fn from(stage: CGOSWDSTAGE) -> Self {
+        Self {
+            timeout: Duration::from_millis(stage.dwTimeout.into()),
+            event: stage.dwEvent.into(),
+        }
+    }
+}
+
+impl TryFrom<WatchdogStage> for CGOSWDSTAGE {
+    type Error = CgosError;
+
+    fn try_from(stage: WatchdogStage) -> Result<Self> {
+        Ok(CGOSWDSTAGE {
+            dwTimeout: milliseconds(stage.timeout)?,
+            dwEvent: stage.event.into(),
+        })
+    }
+}
+
+/// CGOS takes durations as 32 bit milliseconds, about 49 days at most.
+fn milliseconds(duration: Duration) -> Result<u32> {
+    duration
+        .as_millis()
+        .try_into()
+        .map_err(|_| CgosError::InvalidTimeout { timeout: duration })
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug)]
+pub enum WatchdogMode {
+    RebootPc,
+    RestartOs,
+    Staged,
+    /// Value not known to this crate.
+    Unknown(u32),
+}
+
+impl From<WatchdogMode> for u32 {
+    fn from(mode: WatchdogMode) -> Self {
+        match mode {
+            WatchdogMode::RebootPc => CGOS_WDOG_MODE_REBOOT_PC,
+            WatchdogMode::RestartOs => CGOS_WDOG_MODE_RESTART_OS,
+            WatchdogMode::Staged => CGOS_WDOG_MODE_STAGED,
+            WatchdogMode::Unknown(value) => value,
+        }
+    }
+}
+
+impl From<u32> for WatchdogMode {
+    fn from(value: u32) -> Self {
+        match value {
+            CGOS_WDOG_MODE_REBOOT_PC => WatchdogMode::RebootPc,
+            CGOS_WDOG_MODE_RESTART_OS => WatchdogMode::RestartOs,
+            CGOS_WDOG_MODE_STAGED => WatchdogMode::Staged,
+            _ => WatchdogMode::Unknown(value),
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug)]
+pub enum WatchdogOperatingMode {
+    Disabled,
+    OneTimeTrigger,
+    SingleEvent,
+    EventRepeat,
+    /// Value not known to this crate.
+    Unknown(u32),
+}
+
+impl From<WatchdogOperatingMode> for u32 {
+    fn from(operating_mode: WatchdogOperatingMode) -> Self {
+        match operating_mode {
+            WatchdogOperatingMode::Disabled => CGOS_WDOG_OPMODE_DISABLED,
+            WatchdogOperatingMode::OneTimeTrigger => CGOS_WDOG_OPMODE_ONETIME_TRIG,
+            WatchdogOperatingMode::SingleEvent => CGOS_WDOG_OPMODE_SINGLE_EVENT,
+            WatchdogOperatingMode::EventRepeat => CGOS_WDOG_OPMODE_EVENT_REPEAT,
+            WatchdogOperatingMode::Unknown(value) => value,
+        }
+    }
+}
+
+impl From<u32> for WatchdogOperatingMode {
+    fn from(value: u32) -> Self {
+        match value {
+            CGOS_WDOG_OPMODE_DISABLED => WatchdogOperatingMode::Disabled,
+            CGOS_WDOG_OPMODE_ONETIME_TRIG => WatchdogOperatingMode::OneTimeTrigger,
+            CGOS_WDOG_OPMODE_SINGLE_EVENT => WatchdogOperatingMode::SingleEvent,
+            CGOS_WDOG_OPMODE_EVENT_REPEAT => WatchdogOperatingMode::EventRepeat,
+            _ => WatchdogOperatingMode::Unknown(value),
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug)]
+pub enum WatchdogEvent {
+    Interrupt,
+    SystemControlInterrupt,
+    Reset,
+    PowerButton,
+    /// Value not known to this crate.
+    Unknown(u32),
+}
+
+impl From<WatchdogEvent> for u32 {
+    fn from(event: WatchdogEvent) -> Self {
+        match event {
+            WatchdogEvent::Interrupt => CGOS_WDOG_EVENT_INT,
+            WatchdogEvent::SystemControlInterrupt => CGOS_WDOG_EVENT_SCI,
+            WatchdogEvent::Reset => CGOS_WDOG_EVENT_RST,
+            WatchdogEvent::PowerButton => CGOS_WDOG_EVENT_BTN,
+            WatchdogEvent::Unknown(value) => value,
+        }
+    }
+}
+
+impl From<u32> for WatchdogEvent {
+    fn from(value: u32) -> Self {
+        match value {
+            CGOS_WDOG_EVENT_INT => WatchdogEvent::Interrupt,
+            CGOS_WDOG_EVENT_SCI => WatchdogEvent::SystemControlInterrupt,
+            CGOS_WDOG_EVENT_RST => WatchdogEvent::Reset,
+            CGOS_WDOG_EVENT_BTN => WatchdogEvent::PowerButton,
+            _ => WatchdogEvent::Unknown(value),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+    use std::thread::sleep;
+
+    use super::*;
+    use crate::{
+        board::BoardClass,
+        congatec::Congatec,
+        mock::{install, test_lock, with_board, MockBoard, MockWatchdog},
+    };
+
+    fn install_watchdog() {
+        install(vec![MockBoard {
+            watchdogs: vec![MockWatchdog::default()],
+            ..Default::default()
+        }]);
+    }
+
+    #[test]
+    fn set_timeout_arms_a_reboot() {
+        let _lock = test_lock();
+        install_watchdog();
+        let library = Congatec::new().unwrap();
+        let board = library.get_board(BoardClass::ALL, 0).unwrap();
+        assert!(matches!(
+            board.get_watchdog(1),
+            Err(CgosError::IndexOutOfRange {
+                index: 1,
+                amount: 1
+            })
+        ));
+        let watchdog = board.get_watchdog(0).unwrap();
+        watchdog.set_timeout(Duration::from_secs(5)).unwrap();
+        let timeout = Duration::from_secs(u64::MAX);
+        assert!(matches!(
+            watchdog.set_timeout(timeout),
+            Err(CgosError::InvalidTimeout { timeout: rejected }) if rejected == timeout
+        ));
+        with_board(0, |board| {
+            let config = &board.watchdogs[0].config;
+            assert_eq!(config.timeout, Duration::from_secs(5));
+            assert!(matches!(config.mode, WatchdogMode::RebootPc));
+            assert!(matches!(
+                config.operating_mode,
+                WatchdogOperatingMode::SingleEvent
+            ));
+        });
+    }
+
+    #[test]
+    fn config_round_trips_through_the_board() {
+        let _lock = test_lock();
+        install_watchdog();
+        let library = Congatec::new().unwrap();
+        let board = library.get_board(BoardClass::ALL, 0).unwrap();
+        let watchdog = board.get_watchdog(0).unwrap();
+        let config = WatchdogConfig::builder()
+            .delay(Duration::from_millis(100))
+            .operating_mode(WatchdogOperatingMode::EventRepeat)
+            .stage(Duration::from_secs(2), WatchdogEvent::Interrupt)
+            .stage(Duration::from_secs(3), WatchdogEvent::Reset)
+            .build()
+            .unwrap();
+        watchdog.set_config(&config).unwrap();
+        let read = watchdog.config().unwrap();
+        assert_eq!(read.timeout, Duration::from_secs(2));
+        assert_eq!(read.delay, Duration::from_millis(100));
+        assert!(matches!(read.mode, WatchdogMode::Staged));
+        assert!(matches!(
+            read.operating_mode,
+            WatchdogOperatingMode::EventRepeat
+        ));
+        assert!(matches!(
+            read.stages[..],
+            [
+                WatchdogStage {
+                    event: WatchdogEvent::Interrupt,
+                    ..
+                },
+                WatchdogStage {
+                    event: WatchdogEvent::Reset,
+                    timeout,
+                },
+            ] if timeout == Duration::from_secs(3)
+        ));
+    }
+
+    #[test]
+    fn too_many_stages_are_rejected() {
+        let _lock = test_lock();
+        install_watchdog();
+        let library = Congatec::new().unwrap();
+        let board = library.get_board(BoardClass::ALL, 0).unwrap();
+        let watchdog = board.get_watchdog(0).unwrap();
+        let stage = WatchdogStage {
+            timeout: Duration::from_secs(1),
+            event: WatchdogEvent::Reset,
+        };
+        let builder = (0..=CGOS_WDOG_EXPIRE_STAGES)
+            .fold(WatchdogConfig::builder(), |builder, _| {
+                builder.stage(stage.timeout, stage.event)
+            });
+        assert!(matches!(
+            builder.build(),
+            Err(CgosError::TooManyStages {
+                amount: 4,
+                maximum: 3
+            })
+        ));
+        let mut config = watchdog.config().unwrap();
+        config.stages = vec![stage; 4];
+        assert!(matches!(
+            watchdog.set_config(&config),
+            Err(CgosError::TooManyStages {
+                amount: 4,
+                maximum: 3
+            })
+        ));
+        with_board(0, |board| {
+            assert!(board.watchdogs[0].config.stages.is_empty())
+        });
+    }
+
+    #[test]
+    fn unknown_modes_are_passed_through() {
+        let _lock = test_lock();
+        install(vec![MockBoard {
+            watchdogs: vec![MockWatchdog {
+                config: WatchdogConfig {
+                    timeout: Duration::from_secs(1),
+                    delay: Duration::ZERO,
+                    mode: WatchdogMode::Unknown(0x7f),
+                    operating_mode: WatchdogOperatingMode::Unknown(0x7e),
+                    stages: vec![WatchdogStage {
+                        timeout: Duration::from_secs(1),
+                        event: WatchdogEvent::Unknown(0x7d),
+                    }],
+                },
+                triggers: 0,
+            }],
+            ..Default::default()
+        }]);
+        let library = Congatec::new().unwrap();
+        let board = library.get_board(BoardClass::ALL, 0).unwrap();
+        let watchdog = board.get_watchdog(0).unwrap();
+        let config = watchdog.config().unwrap();
+        assert!(matches!(config.mode, WatchdogMode::Unknown(0x7f)));
+        assert!(matches!(
+            config.operating_mode,
+            WatchdogOperatingMode::Unknown(0x7e)
+        ));
+        assert!(matches!(
+            config.stages[..],
+            [WatchdogStage {
+                event: WatchdogEvent::Unknown(0x7d),
+                ..
+            }]
+        ));
+        with_board(0, |board| board.watchdogs[0].config.stages.clear());
+        watchdog.set_config(&config).unwrap();
+        with_board(0, |board| {
+            assert!(matches!(
+                board.watchdogs[0].config.stages[..],
+                [WatchdogStage {
+                    event: WatchdogEvent::Unknown(0x7d),
+                    ..
+                }]
+            ));
+        });
+    }
+
+    #[test]
+    fn keep_alive_kicks_until_unhealthy_or_dropped() {
+        let _lock = test_lock();
+        install_watchdog();
+        let library = Congatec::new().unwrap();
+        let board = library.get_board(BoardClass::ALL, 0).unwrap();
+        let watchdog = board.get_watchdog(0).unwrap();
+        watchdog.set_timeout(Duration::from_secs(5)).unwrap();
+        let mut checks = 0;
+        let keeper = watchdog.keep_alive(Duration::from_millis(1), move || {
+            checks += 1;
+            checks <= 3
+        });
+        sleep(Duration::from_millis(100));
+        drop(keeper);
+        with_board(0, |board| assert_eq!(board.watchdogs[0].triggers, 3));
+
+        let keeper = watchdog.keep_alive(Duration::from_secs(3600), || true);
+        drop(keeper);
+        with_board(0, |board| {
+            assert_eq!(board.watchdogs[0].triggers, 4);
+            assert!(matches!(
+                board.watchdogs[0].config.operating_mode,
+                WatchdogOperatingMode::SingleEvent
+            ));
+        });
+    }
+
+    #[test]
+    fn guard_disables_on_drop_unless_forgotten() {
+        let _lock = test_lock();
+        install_watchdog();
+        let library = Congatec::new().unwrap();
+        let board = library.get_board(BoardClass::ALL, 0).unwrap();
+        let watchdog = board.get_watchdog(0).unwrap();
+        let operating_mode = || with_board(0, |board| board.watchdogs[0].config.operating_mode);
+
+        let guard = watchdog.arm(Duration::from_secs(5)).unwrap();
+        guard.trigger().unwrap();
+        assert!(matches!(
+            operating_mode(),
+            WatchdogOperatingMode::SingleEvent
+        ));
+        drop(guard);
+        assert!(matches!(operating_mode(), WatchdogOperatingMode::Disabled));
+
+        watchdog.arm(Duration::from_secs(5)).unwrap().forget();
+        assert!(matches!(
+            operating_mode(),
+            WatchdogOperatingMode::SingleEvent
+        ));
+        with_board(0, |board| assert_eq!(board.watchdogs[0].triggers, 1));
+    }
+}